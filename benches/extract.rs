@@ -0,0 +1,37 @@
+//! Compare the analyser pass, which resolves the player along the way,
+//! with the separate player search pass that used to run before it
+//!
+//! Run with `AMMOCOUNT_BENCH_DEMO=path/to/demo.dem cargo bench`,
+//! optionally setting `AMMOCOUNT_BENCH_PLAYER` to a name or steam id.
+
+use ammocount::{analyse, find_player, LookupOptions};
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::env;
+use std::fs;
+
+fn bench_extract(c: &mut Criterion) {
+    let path = match env::var("AMMOCOUNT_BENCH_DEMO") {
+        Ok(path) => path,
+        Err(_) => {
+            eprintln!("AMMOCOUNT_BENCH_DEMO not set, skipping benchmark");
+            return;
+        }
+    };
+    let player = env::var("AMMOCOUNT_BENCH_PLAYER").ok();
+    let player = player.as_deref();
+    let demo = fs::read(path).expect("failed to read demo");
+    let lookup = LookupOptions::default();
+
+    let mut group = c.benchmark_group("extract");
+    group.sample_size(10);
+    group.bench_function("find_player", |b| {
+        b.iter(|| find_player(&demo, player, &lookup).unwrap())
+    });
+    group.bench_function("analyse", |b| {
+        b.iter(|| analyse(&demo, player, &lookup).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_extract);
+criterion_main!(benches);
@@ -0,0 +1,796 @@
+use crate::killfeed::{Crit, Kill, KillFeedPlayer, KillType};
+use crate::lookup::LookupOptions;
+use crate::playersearch::{PlayerResolver, UserEntry};
+use fnv::FnvHashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tf_demo_parser::demo::gameevent_gen::GameEvent;
+use tf_demo_parser::demo::message::packetentities::{EntityId, PacketEntity};
+use tf_demo_parser::demo::message::Message;
+use tf_demo_parser::demo::packet::datatable::{
+    ClassId, ParseSendTable, ServerClass, ServerClassName,
+};
+use tf_demo_parser::demo::packet::message::MessagePacketMeta;
+use tf_demo_parser::demo::packet::stringtable::StringTableEntry;
+use tf_demo_parser::demo::parser::gamestateanalyser::{Team, UserId};
+use tf_demo_parser::demo::parser::MessageHandler;
+use tf_demo_parser::demo::sendprop::{SendPropIdentifier, SendPropValue};
+use tf_demo_parser::demo::vector::Vector;
+use tf_demo_parser::{MessageType, ParserState};
+use tracing::warn;
+
+/// The state of the player at a single tick
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TickData {
+    pub tick: u32,
+    pub ammo: u16,
+    pub max_ammo: u16,
+    pub health: u16,
+    pub uber: Option<u8>,
+    /// Pitch and yaw, only set on ticks where the view angles were updated
+    pub angles: [Option<f32>; 2],
+    /// Damage dealt by the player during this tick
+    pub hit: Option<u32>,
+    /// Weapon id (`TF_WEAPON_*`) of the weapon that dealt the damage in `hit`
+    pub hit_weapon: Option<u16>,
+    pub weapon: String,
+    pub position: Vector,
+    /// Number of kills made by the player during this tick
+    pub kills: u16,
+    /// Number of kills made by the player since they last died
+    pub life_kills: u16,
+    /// Whether the player damaged an enemy that was airborne for more than a few ticks during this tick
+    pub airshot: bool,
+    /// Number of medics with a full uber killed by the player during this tick
+    pub uber_drops: u16,
+    /// Every hit on an enemy during this tick
+    pub hits: Vec<Hit>,
+    /// Damage dealt to enemies since the player last died
+    pub life_damage: u32,
+}
+
+/// Damage dealt to an enemy
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Hit {
+    pub damage: u32,
+    /// Weapon id (`TF_WEAPON_*`) of the weapon that dealt the damage
+    pub weapon_id: u16,
+    /// Position of the damaged player, `None` if unknown
+    pub victim_position: Option<Vector>,
+}
+
+/// Which players to extract the data for
+#[derive(Clone, Debug)]
+pub enum PlayerSelection {
+    /// Players by name or steam id, `None` for the player that recorded the demo
+    Players(Vec<Option<String>>),
+    /// Every player with a steam id that was connected at some point during the demo
+    All,
+}
+
+impl From<Option<&str>> for PlayerSelection {
+    /// A single player by name or steam id, or the player that recorded the demo
+    fn from(player: Option<&str>) -> Self {
+        PlayerSelection::Players(vec![player.map(String::from)])
+    }
+}
+
+/// The per-tick data for a single player
+#[derive(Clone, Debug, Default)]
+pub struct PlayerTicks {
+    pub name: String,
+    /// SteamID3 (`[U:1:...]`), `None` for bots
+    pub steam_id: Option<String>,
+    pub ticks: Vec<TickData>,
+    /// Every kill in the demo
+    pub kills: Vec<Kill>,
+    pub errors: Errors,
+}
+
+/// The state of a single tracked player
+#[derive(Default)]
+struct PlayerState {
+    player: PlayerResolver,
+    output: Vec<TickData>,
+    current_health: u16,
+    active_weapon: i64,
+    ammo: [u16; 2],
+    max_ammo: [u16; 2],
+    uber: u8,
+    has_uber: bool,
+    angles: [f32; 2],
+    errors: Errors,
+    hit: Option<u32>,
+    hit_weapon: Option<u16>,
+    tick_angles: [Option<f32>; 2],
+    angle_delta_tick: [u32; 2],
+    loadout: [i64; 2],
+    position: Vector,
+    kills: u16,
+    life_kills: u16,
+    airshot: bool,
+    uber_drops: u16,
+    hits: Vec<Hit>,
+    life_damage: u32,
+}
+
+impl PlayerState {
+    fn new(player: Option<String>, lookup: &LookupOptions) -> Self {
+        PlayerState {
+            player: PlayerResolver::new(player, lookup),
+            ..Default::default()
+        }
+    }
+
+    fn is_entity(&self, entity: EntityId) -> bool {
+        matches!(self.player.player(), Some((local_entity, _)) if local_entity == entity)
+    }
+
+    fn is_user(&self, user: UserId) -> bool {
+        matches!(self.player.player(), Some((_, local_user)) if local_user == user)
+    }
+
+    fn into_output(self, kills: &[Kill]) -> Result<PlayerTicks, PlayerResolver> {
+        if !self.player.is_resolved() {
+            return Err(self.player);
+        }
+        let (name, steam_id) = match self.player.chosen() {
+            Some(candidate) => (
+                candidate.name.clone(),
+                candidate.steam_id.map(|steam_id| steam_id.steam3()),
+            ),
+            None => (String::new(), None),
+        };
+        Ok(PlayerTicks {
+            name,
+            steam_id,
+            ticks: self.output,
+            kills: kills.to_vec(),
+            errors: self.errors,
+        })
+    }
+}
+
+#[derive(Default)]
+pub struct AmmoCountAnalyser {
+    tick: u32,
+    max_clip: FnvHashMap<EntityId, u16>,
+    clip: FnvHashMap<EntityId, u16>,
+    class_names: Vec<ServerClassName>,
+    players: Vec<PlayerState>,
+    /// Start tracking every player that joins
+    all_players: bool,
+    lookup: LookupOptions,
+    entity_classes: FnvHashMap<EntityId, ClassId>,
+    outer_map: FnvHashMap<i64, EntityId>,
+    model_names: Vec<String>,
+    last_tick: u32,
+    pov: EntityId,
+    model_indexes: HashMap<EntityId, u32>,
+    users: FnvHashMap<UserId, UserEntry>,
+    teams: FnvHashMap<EntityId, Team>,
+    /// Tick the player entity left the ground, for players that are airborne
+    airborne_since: FnvHashMap<EntityId, u32>,
+    /// Uber percentage of every player
+    charge_levels: FnvHashMap<EntityId, u8>,
+    /// Position of every player except the player that recorded the demo
+    positions: FnvHashMap<EntityId, Vector>,
+    kills: Vec<Kill>,
+}
+
+impl MessageHandler for AmmoCountAnalyser {
+    /// Fails with the resolver for every player that couldn't be decided on during parsing
+    type Output = Vec<Result<PlayerTicks, PlayerResolver>>;
+
+    fn does_handle(_message_type: MessageType) -> bool {
+        true
+    }
+
+    fn handle_message(&mut self, message: &Message, tick: u32, state: &ParserState) {
+        for player in &mut self.players {
+            player.player.handle_message(message, state);
+        }
+        match message {
+            Message::ServerInfo(info) => {
+                self.pov = (info.player_slot as u32 + 1).into();
+            }
+            Message::PacketEntities(entities) => {
+                for entity in &entities.entities {
+                    self.handle_entity(tick, entity, state)
+                }
+            }
+            Message::GameEvent(event_msg) => {
+                self.handle_event(&event_msg.event);
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_string_entry(
+        &mut self,
+        table: &str,
+        index: usize,
+        entry: &StringTableEntry,
+        _state: &ParserState,
+    ) {
+        match table {
+            "modelprecache" => {
+                let model = entry.text.as_deref().unwrap_or_default();
+                let file_name = model.rsplit_once('/').unwrap_or_default().1;
+                let name = file_name.split_once('.').unwrap_or_default().0;
+                self.model_names.insert(index, name.to_string())
+            }
+            "userinfo" => {
+                if let Some(entry) = UserEntry::parse(index, entry) {
+                    self.handle_user(index, entry);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_data_tables(
+        &mut self,
+        _parse_tables: &[ParseSendTable],
+        server_classes: &[ServerClass],
+        _state: &ParserState,
+    ) {
+        self.class_names = server_classes
+            .iter()
+            .map(|class| &class.name)
+            .cloned()
+            .collect();
+    }
+
+    fn handle_packet_meta(&mut self, tick: u32, meta: &MessagePacketMeta, _state: &ParserState) {
+        let pov = self.pov;
+        if let Some(player) = self.players.iter_mut().find(|player| player.is_entity(pov)) {
+            player.angles = [meta.view_angles[0].angles.x, meta.view_angles[0].angles.y];
+            player.position = meta.view_angles[0].origin;
+            player.tick_angles = [Some(player.angles[0]), Some(player.angles[1])];
+        }
+        self.tick = tick;
+    }
+
+    fn into_output(self, _state: &ParserState) -> Self::Output {
+        let kills = self.kills;
+        self.players
+            .into_iter()
+            .map(|player| player.into_output(&kills))
+            .collect()
+    }
+}
+
+const CLIP_PROP: SendPropIdentifier = SendPropIdentifier::new("DT_LocalWeaponData", "m_iClip1");
+const OUTER_CONTAINER_PROP: SendPropIdentifier =
+    SendPropIdentifier::new("DT_AttributeContainer", "m_hOuter");
+const ACTIVE_WEAPON_PROP: SendPropIdentifier =
+    SendPropIdentifier::new("DT_BaseCombatCharacter", "m_hActiveWeapon");
+pub(crate) const TEAM_PROP: SendPropIdentifier =
+    SendPropIdentifier::new("DT_BaseEntity", "m_iTeamNum");
+const FLAGS_PROP: SendPropIdentifier = SendPropIdentifier::new("DT_BasePlayer", "m_fFlags");
+const HEALTH_PROP: SendPropIdentifier = SendPropIdentifier::new("DT_BasePlayer", "m_iHealth");
+#[allow(dead_code)]
+const UBER_CHARGE_PROP: SendPropIdentifier =
+    SendPropIdentifier::new("DT_TFWeaponMedigunDataNonLocal", "m_flChargeLevel");
+#[allow(dead_code)]
+const UBER_CHARGE_PROP_LOCAL: SendPropIdentifier =
+    SendPropIdentifier::new("DT_LocalTFWeaponMedigunData", "m_flChargeLevel");
+#[allow(dead_code)]
+const DAMAGE_PROP_LOCAL: SendPropIdentifier =
+    SendPropIdentifier::new("DT_TFPlayerScoringDataExclusive", "m_iDamageDone");
+#[allow(dead_code)]
+const EYE_ANGLES_X: SendPropIdentifier =
+    SendPropIdentifier::new("DT_TFNonLocalPlayerExclusive", "m_angEyeAngles[0]");
+#[allow(dead_code)]
+const EYE_ANGLES_Y: SendPropIdentifier =
+    SendPropIdentifier::new("DT_TFNonLocalPlayerExclusive", "m_angEyeAngles[1]");
+#[allow(dead_code)]
+const EYE_POS_X: SendPropIdentifier =
+    SendPropIdentifier::new("DT_TFNonLocalPlayerExclusive", "m_vecOrigin[0]");
+#[allow(dead_code)]
+const EYE_POS_Y: SendPropIdentifier =
+    SendPropIdentifier::new("DT_TFNonLocalPlayerExclusive", "m_vecOrigin[1]");
+#[allow(dead_code)]
+const EYE_POS_Z: SendPropIdentifier =
+    SendPropIdentifier::new("DT_TFNonLocalPlayerExclusive", "m_vecOrigin[2]");
+
+#[allow(dead_code)]
+const WEAPON1_ID_PROP: SendPropIdentifier = SendPropIdentifier::new("m_hMyWeapons", "000");
+#[allow(dead_code)]
+const WEAPON2_ID_PROP: SendPropIdentifier = SendPropIdentifier::new("m_hMyWeapons", "001");
+#[allow(dead_code)]
+const WEAPON3_ID_PROP: SendPropIdentifier = SendPropIdentifier::new("m_hMyWeapons", "002");
+
+#[allow(dead_code)]
+const AMMO1_PROP: SendPropIdentifier = SendPropIdentifier::new("m_iAmmo", "001");
+#[allow(dead_code)]
+const AMMO2_PROP: SendPropIdentifier = SendPropIdentifier::new("m_iAmmo", "002");
+
+#[allow(dead_code)]
+const MODEL_INDEX: SendPropIdentifier =
+    SendPropIdentifier::new("DT_BaseCombatWeapon", "m_iWorldModelIndex");
+
+const OUTER_NULL: i64 = 0x1FFFFF;
+/// `FL_ONGROUND` player flag
+const FLAG_ON_GROUND: i64 = 1;
+/// Minimum number of ticks the victim has to be airborne for a hit to count as airshot, ignoring small hops and steps
+const MIN_AIRBORNE_TICKS: u32 = 10;
+/// Uber percentage at which killing a medic counts as forcing an uber drop
+const FULL_CHARGE: u8 = 100;
+
+impl AmmoCountAnalyser {
+    /// Track the player matching the name or steam id, or the player that recorded the demo if no player is given
+    ///
+    /// The player is resolved from the `userinfo` table during the same pass,
+    /// no data is recorded for the player until they're found.
+    pub fn new(player: Option<String>, lookup: &LookupOptions) -> Self {
+        AmmoCountAnalyser {
+            players: vec![PlayerState::new(player, lookup)],
+            ..Default::default()
+        }
+    }
+
+    /// Track multiple players at once, the output contains one entry per player in the same order
+    ///
+    /// When all players are selected, players are added in the order they join.
+    pub fn with_players(selection: &PlayerSelection, lookup: &LookupOptions) -> Self {
+        let players = match selection {
+            PlayerSelection::Players(players) => players
+                .iter()
+                .map(|player| PlayerState::new(player.clone(), lookup))
+                .collect(),
+            PlayerSelection::All => Vec::new(),
+        };
+        AmmoCountAnalyser {
+            players,
+            all_players: matches!(selection, PlayerSelection::All),
+            lookup: lookup.clone(),
+            ..Default::default()
+        }
+    }
+
+    fn handle_user(&mut self, index: usize, entry: Option<UserEntry>) {
+        if let Some(entry) = &entry {
+            self.users.insert(entry.user, entry.clone());
+        }
+        if self.all_players {
+            if let Some(steam_id) = entry.as_ref().and_then(|entry| entry.steam_id) {
+                let tracked = self.players.iter().any(|player| {
+                    player
+                        .player
+                        .chosen()
+                        .and_then(|candidate| candidate.steam_id)
+                        == Some(steam_id)
+                });
+                if !tracked {
+                    self.players
+                        .push(PlayerState::new(Some(steam_id.steam3()), &self.lookup));
+                }
+            }
+        }
+        for player in &mut self.players {
+            player.player.handle_user(index, entry.clone());
+        }
+    }
+
+    /// The name and team of a user for the kill feed
+    fn kill_feed_player(&self, user: u16) -> Option<KillFeedPlayer> {
+        let entry = self.users.get(&UserId::from(user))?;
+        let team = self
+            .teams
+            .get(&entry.entity)
+            .copied()
+            .unwrap_or(Team::Other);
+        Some(KillFeedPlayer::new(entry.name.clone(), team))
+    }
+
+    fn user_entity(&self, user: u16) -> Option<EntityId> {
+        self.users
+            .get(&UserId::from(user))
+            .map(|entry| entry.entity)
+    }
+
+    fn player_for_entity(&mut self, entity: EntityId) -> Option<&mut PlayerState> {
+        self.players
+            .iter_mut()
+            .find(|player| player.is_entity(entity))
+    }
+
+    #[allow(dead_code)]
+    fn server_class(&self, id: ClassId) -> &str {
+        self.class_names[u16::from(id) as usize].as_str()
+    }
+
+    fn handle_event(&mut self, event: &GameEvent) {
+        match event {
+            GameEvent::PlayerSpawn(spawn) => {
+                let user = UserId::from(spawn.user_id);
+                if let Some(player) = self.players.iter().find(|player| player.is_user(user)) {
+                    // refill the clips of the weapons of the player that spawned
+                    for handle in &player.loadout {
+                        if let Some(weapon) = self.outer_map.get(handle) {
+                            if let Some(max_clip) = self.max_clip.get(weapon) {
+                                self.clip.insert(*weapon, *max_clip);
+                            }
+                        }
+                    }
+                }
+            }
+            GameEvent::PlayerHurt(event) => {
+                let attacker = UserId::from(event.attacker);
+                let damage = event.damage_amount as u32;
+                let self_damage = event.user_id == event.attacker;
+                let tick = self.tick;
+                let victim = self.user_entity(event.user_id);
+                let airborne = !self_damage
+                    && matches!(
+                        victim.and_then(|victim| self.airborne_since.get(&victim)),
+                        Some(since) if tick >= since + MIN_AIRBORNE_TICKS
+                    );
+                let victim_position =
+                    victim.and_then(|victim| self.positions.get(&victim).copied());
+
+                if let Some(player) = self
+                    .players
+                    .iter_mut()
+                    .find(|player| player.is_user(attacker))
+                {
+                    player.hit = Some(damage);
+                    player.hit_weapon = Some(event.weapon_id);
+                    player.airshot |= airborne;
+                    if !self_damage {
+                        player.hits.push(Hit {
+                            damage,
+                            weapon_id: event.weapon_id,
+                            victim_position,
+                        });
+                        player.life_damage += damage;
+                    }
+                }
+            }
+            GameEvent::PlayerDeath(death) => {
+                let victim = UserId::from(death.user_id);
+                let attacker = UserId::from(death.attacker);
+                let uber_drop = matches!(
+                    self.user_entity(death.user_id)
+                        .and_then(|victim| self.charge_levels.get(&victim)),
+                    Some(charge) if *charge >= FULL_CHARGE
+                );
+                for player in &mut self.players {
+                    if player.is_user(victim) {
+                        player.life_kills = 0;
+                        player.life_damage = 0;
+                    } else if player.is_user(attacker) {
+                        player.kills += 1;
+                        player.life_kills += 1;
+                        if uber_drop {
+                            player.uber_drops += 1;
+                        }
+                    }
+                }
+
+                if let Some(victim) = self.kill_feed_player(death.user_id) {
+                    let attacker = if death.attacker == death.user_id {
+                        None
+                    } else {
+                        self.kill_feed_player(death.attacker)
+                    };
+                    self.kills.push(Kill {
+                        tick: self.tick,
+                        attacker,
+                        victim,
+                        assister: self.kill_feed_player(death.assister),
+                        weapon: death.weapon.to_string(),
+                        crit: Crit::new(death.damage_bits, death.crit_type),
+                        kill_type: KillType::new(death.custom_kill),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_entity(&mut self, tick: u32, entity: &PacketEntity, state: &ParserState) {
+        let pov = self.pov;
+        for prop in entity.props(state) {
+            match prop.value {
+                SendPropValue::Integer(value) if value != OUTER_NULL => {
+                    if let Some((table_name, prop_name)) = prop.identifier.names() {
+                        if table_name == "m_iChargeLevel" {
+                            let entity_id: u32 = prop_name.parse().unwrap();
+                            self.charge_levels
+                                .insert(EntityId::from(entity_id), value as u8);
+                            if let Some(player) = self.player_for_entity(EntityId::from(entity_id))
+                            {
+                                if value > 0 {
+                                    player.has_uber = true;
+                                }
+                                player.uber = value as u8;
+                            }
+                        }
+                    }
+                    match prop.identifier {
+                        OUTER_CONTAINER_PROP => {
+                            self.outer_map.insert(value, entity.entity_index);
+                        }
+                        TEAM_PROP => {
+                            self.teams.insert(entity.entity_index, Team::new(value));
+                        }
+                        FLAGS_PROP => {
+                            if value & FLAG_ON_GROUND != 0 {
+                                self.airborne_since.remove(&entity.entity_index);
+                            } else {
+                                self.airborne_since
+                                    .entry(entity.entity_index)
+                                    .or_insert(tick);
+                            }
+                        }
+                        CLIP_PROP => {
+                            match self.entity_classes.get(&entity.entity_index) {
+                                Some(class) if *class != entity.server_class => {
+                                    self.max_clip.insert(entity.entity_index, value as u16);
+                                }
+                                _ => {
+                                    let clip_max =
+                                        self.max_clip.entry(entity.entity_index).or_default();
+                                    *clip_max = (*clip_max).max(value as u16);
+                                }
+                            }
+                            self.clip.insert(entity.entity_index, value as u16);
+                        }
+                        MODEL_INDEX => {
+                            self.model_indexes.insert(entity.entity_index, value as u32);
+                        }
+                        _ => {
+                            if let Some(player) = self.player_for_entity(entity.entity_index) {
+                                player.handle_integer_prop(prop.identifier, value);
+                            }
+                        }
+                    }
+                }
+                SendPropValue::Float(value) => {
+                    match prop.identifier {
+                        EYE_POS_X => {
+                            self.positions.entry(entity.entity_index).or_default().x = value
+                        }
+                        EYE_POS_Y => {
+                            self.positions.entry(entity.entity_index).or_default().y = value
+                        }
+                        EYE_POS_Z => {
+                            self.positions.entry(entity.entity_index).or_default().z = value
+                        }
+                        _ => {}
+                    }
+                    if let Some(player) = self.player_for_entity(entity.entity_index) {
+                        if !player.is_entity(pov) {
+                            player.handle_float_prop(prop.identifier, value, tick);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        self.entity_classes
+            .insert(entity.entity_index, entity.server_class);
+
+        if self.tick > self.last_tick {
+            for index in 0..self.players.len() {
+                if self.players[index].player.player().is_some() {
+                    self.push_tick(index);
+                }
+            }
+            self.last_tick = self.tick;
+        }
+    }
+
+    /// Record the current state of a player for the current tick
+    fn push_tick(&mut self, index: usize) {
+        let tick = self.tick;
+        let player = &mut self.players[index];
+        let active_slot = if player.active_weapon == player.loadout[0] {
+            0
+        } else {
+            1
+        };
+        if let Some(active_weapon) = self.outer_map.get(&player.active_weapon) {
+            if self.clip.contains_key(active_weapon) {
+                let mut ammo = if self.max_clip[active_weapon] > 0 {
+                    self.clip[active_weapon].saturating_sub(1)
+                } else {
+                    player.ammo[active_slot]
+                };
+                let mut max_ammo = if self.max_clip[active_weapon] > 0 {
+                    self.max_clip[active_weapon].saturating_sub(1)
+                } else {
+                    player.max_ammo[active_slot]
+                };
+
+                let model_index = self
+                    .model_indexes
+                    .get(active_weapon)
+                    .copied()
+                    .unwrap_or_default();
+
+                let weapon = self
+                    .model_names
+                    .get(model_index as usize)
+                    .cloned()
+                    .unwrap_or_default();
+
+                if let Some(max_overwrite) = max_clip_overwrite(&weapon) {
+                    max_ammo = max_overwrite;
+                }
+                ammo = ammo.min(max_ammo);
+
+                player.output.push(TickData {
+                    tick,
+                    ammo,
+                    max_ammo,
+                    health: player.current_health,
+                    uber: player.has_uber.then(|| player.uber),
+                    angles: player.tick_angles,
+                    hit: player.hit,
+                    hit_weapon: player.hit_weapon,
+                    weapon,
+                    position: player.position,
+                    kills: player.kills,
+                    life_kills: player.life_kills,
+                    airshot: player.airshot,
+                    uber_drops: player.uber_drops,
+                    hits: std::mem::take(&mut player.hits),
+                    life_damage: player.life_damage,
+                });
+
+                player.hit = None;
+                player.hit_weapon = None;
+                player.kills = 0;
+                player.airshot = false;
+                player.uber_drops = 0;
+                player.tick_angles = [None, None];
+            } else {
+                player.errors.clip_not_found += 1;
+                warn!(
+                    tick,
+                    weapon_handle = player.active_weapon,
+                    weapon_id = display(active_weapon),
+                    "can't find clip"
+                );
+            }
+        } else if player.active_weapon > 0 {
+            player.errors.weapon_not_found += 1;
+            warn!(
+                tick,
+                weapon_handle = player.active_weapon,
+                "can't find weapon"
+            );
+        } else {
+            player.errors.no_weapon_set += 1;
+        }
+    }
+}
+
+impl PlayerState {
+    fn handle_integer_prop(&mut self, prop: SendPropIdentifier, value: i64) {
+        match prop {
+            ACTIVE_WEAPON_PROP => {
+                self.active_weapon = value;
+            }
+            AMMO1_PROP => {
+                self.ammo[0] = value as u16;
+                self.max_ammo[0] = self.max_ammo[0].max(value as u16);
+            }
+            AMMO2_PROP => {
+                self.ammo[1] = value as u16;
+                self.max_ammo[1] = self.max_ammo[1].max(value as u16);
+            }
+            HEALTH_PROP => {
+                self.current_health = value as u16;
+            }
+            WEAPON1_ID_PROP => {
+                if value != self.loadout[0] {
+                    self.max_ammo[0] = 0;
+                    self.ammo[0] = 0;
+                    self.loadout[0] = value;
+                }
+            }
+            WEAPON2_ID_PROP => {
+                if value != self.loadout[1] {
+                    self.max_ammo[1] = 0;
+                    self.ammo[1] = 0;
+                    self.loadout[1] = value;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_float_prop(&mut self, prop: SendPropIdentifier, value: f32, tick: u32) {
+        match prop {
+            EYE_ANGLES_X => {
+                self.angles[0] = value;
+                self.tick_angles[0] = Some(value);
+                self.angle_delta_tick[0] = tick;
+            }
+            EYE_ANGLES_Y => {
+                self.angles[1] = value;
+                self.tick_angles[1] = Some(value);
+                self.angle_delta_tick[1] = tick;
+            }
+            EYE_POS_X => {
+                self.position.x = value;
+            }
+            EYE_POS_Y => {
+                self.position.y = value;
+            }
+            EYE_POS_Z => {
+                self.position.z = value;
+            }
+            _ => {}
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Errors {
+    no_weapon_set: u32,
+    weapon_not_found: u32,
+    clip_not_found: u32,
+}
+
+impl Errors {
+    pub fn show(&self) {
+        if self.no_weapon_set > 0 {
+            eprint!("No weapon set {} times", self.no_weapon_set);
+        }
+        if self.weapon_not_found > 0 {
+            eprint!("Weapon not found {} times", self.weapon_not_found);
+        }
+        if self.clip_not_found > 0 {
+            eprint!("Clip not found {} times", self.clip_not_found);
+        }
+    }
+}
+
+/// Name of a weapon id (`TF_WEAPON_*`) as used in the hurt events, the id itself for less common weapon types
+pub(crate) fn weapon_name(weapon_id: u16) -> String {
+    let name = match weapon_id {
+        1 => "bat",
+        2 => "bat_wood",
+        3 => "bottle",
+        4 => "fireaxe",
+        5 => "club",
+        6 => "crowbar",
+        7 => "knife",
+        8 => "fists",
+        9 => "shovel",
+        10 => "wrench",
+        11 => "bonesaw",
+        12 => "shotgun_primary",
+        13 => "shotgun_soldier",
+        14 => "shotgun_hwg",
+        15 => "shotgun_pyro",
+        16 => "scattergun",
+        17 => "sniperrifle",
+        18 => "minigun",
+        19 => "smg",
+        20 => "syringegun_medic",
+        22 => "rocketlauncher",
+        23 => "grenadelauncher",
+        24 => "pipebomblauncher",
+        25 => "flamethrower",
+        41 => "pistol",
+        42 => "pistol_scout",
+        43 => "revolver",
+        _ => return weapon_id.to_string(),
+    };
+    name.to_string()
+}
+
+fn max_clip_overwrite(weapon: &str) -> Option<u16> {
+    match weapon {
+        "c_pep_pistol" => Some(9),
+        _ => None,
+    }
+}
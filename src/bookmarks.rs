@@ -0,0 +1,175 @@
+use crate::clips::Clip;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Bookmark files written next to the demos by tf2's demo support and by P-REC
+const BOOKMARK_FILES: &[&str] = &["_events.txt", "killstreaks.txt"];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BookmarkKind {
+    /// A kill streak reached the given number of kills
+    KillStreak(u32),
+    /// Bookmark set manually while recording
+    Bookmark,
+}
+
+/// A single entry from a bookmark file
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Bookmark {
+    /// Name of the demo without extension
+    pub demo: String,
+    pub tick: u32,
+    pub kind: BookmarkKind,
+}
+
+impl Bookmark {
+    /// Parse a line like `[2022/05/12 21:34] Kill Streak 3 ("demo" at 12345)`
+    fn parse(line: &str) -> Option<Self> {
+        let line = line.trim();
+        let event = line.split_once(']').map_or(line, |(_, event)| event);
+        let (event, target) = event.rsplit_once('(')?;
+        let (demo, tick) = target.strip_suffix(')')?.rsplit_once(" at ")?;
+        let demo = demo.trim().trim_matches('"');
+        let tick = tick.trim().parse().ok()?;
+
+        let event = event.to_ascii_lowercase().replace(' ', "");
+        let kind = match event.find("killstreak") {
+            Some(start) => {
+                let count = event[start + "killstreak".len()..]
+                    .trim_start_matches(':')
+                    .trim();
+                BookmarkKind::KillStreak(count.parse().unwrap_or_default())
+            }
+            None => BookmarkKind::Bookmark,
+        };
+        Some(Bookmark {
+            demo: demo.to_string(),
+            tick,
+            kind,
+        })
+    }
+
+    fn label(&self) -> String {
+        match self.kind {
+            BookmarkKind::KillStreak(count) => format!("killstreak_{}_{}", count, self.tick),
+            BookmarkKind::Bookmark => format!("bookmark_{}", self.tick),
+        }
+    }
+}
+
+/// Parse all bookmarks in a `_events.txt` or `killstreaks.txt` file, lines that aren't bookmarks are ignored
+pub fn parse_bookmarks(content: &str) -> Vec<Bookmark> {
+    content.lines().filter_map(Bookmark::parse).collect()
+}
+
+pub fn load_bookmarks(path: impl AsRef<Path>) -> Result<Vec<Bookmark>, String> {
+    let path = path.as_ref();
+    fs::read_to_string(path)
+        .map(|content| parse_bookmarks(&content))
+        .map_err(|e| format!("failed to read bookmarks {}: {}", path.display(), e))
+}
+
+/// Find a bookmark file in the same directory as the demo
+pub fn find_bookmark_file(demo: &Path) -> Option<PathBuf> {
+    let dir = demo.parent()?;
+    BOOKMARK_FILES
+        .iter()
+        .map(|name| dir.join(name))
+        .find(|path| path.is_file())
+}
+
+/// Turn the bookmarks for a demo into clips, from `pre_roll` ticks before to `post_roll` ticks after each bookmark
+///
+/// Overlapping clips, like the entries of a single kill streak, are merged and labeled after the last bookmark.
+pub fn bookmark_clips(
+    bookmarks: &[Bookmark],
+    demo: &str,
+    pre_roll: u32,
+    post_roll: u32,
+) -> Vec<Clip> {
+    let mut bookmarks: Vec<&Bookmark> = bookmarks
+        .iter()
+        .filter(|bookmark| bookmark.demo == demo)
+        .collect();
+    bookmarks.sort_by_key(|bookmark| bookmark.tick);
+
+    let mut clips: Vec<Clip> = Vec::new();
+    for bookmark in bookmarks {
+        let start = bookmark.tick.saturating_sub(pre_roll);
+        let end = bookmark.tick.saturating_add(post_roll);
+        match clips.last_mut() {
+            Some(last) if start <= last.end => {
+                last.end = end;
+                last.label = Some(bookmark.label());
+            }
+            _ => clips.push(Clip {
+                player: None,
+                start,
+                end,
+                label: Some(bookmark.label()),
+            }),
+        }
+    }
+    clips
+}
+
+#[test]
+fn test_parse_bookmarks() {
+    let content = r#"
+[2022/05/12 21:34] Kill Streak 3 ("2022-05-12_21-30-00" at 12345)
+[2022/05/12 21:35] Bookmark ("2022-05-12_21-30-00" at 23456)
+>
+[2013/10/12 / 20:20] Kill Streak:5 ("pov_match" at 3456)
+[2013/10/12 / 20:21] Player bookmark ("pov_match" at 4000)
+"#;
+    assert_eq!(
+        vec![
+            Bookmark {
+                demo: "2022-05-12_21-30-00".to_string(),
+                tick: 12345,
+                kind: BookmarkKind::KillStreak(3),
+            },
+            Bookmark {
+                demo: "2022-05-12_21-30-00".to_string(),
+                tick: 23456,
+                kind: BookmarkKind::Bookmark,
+            },
+            Bookmark {
+                demo: "pov_match".to_string(),
+                tick: 3456,
+                kind: BookmarkKind::KillStreak(5),
+            },
+            Bookmark {
+                demo: "pov_match".to_string(),
+                tick: 4000,
+                kind: BookmarkKind::Bookmark,
+            },
+        ],
+        parse_bookmarks(content)
+    );
+}
+
+#[test]
+fn test_bookmark_clips() {
+    let bookmark = |tick, kind| Bookmark {
+        demo: "demo".to_string(),
+        tick,
+        kind,
+    };
+    let bookmarks = vec![
+        bookmark(1000, BookmarkKind::KillStreak(1)),
+        bookmark(1200, BookmarkKind::KillStreak(2)),
+        bookmark(5000, BookmarkKind::Bookmark),
+        Bookmark {
+            demo: "other".to_string(),
+            tick: 2000,
+            kind: BookmarkKind::Bookmark,
+        },
+    ];
+    let clips = bookmark_clips(&bookmarks, "demo", 500, 300);
+    assert_eq!(2, clips.len());
+    assert_eq!((500, 1500), (clips[0].start, clips[0].end));
+    assert_eq!(Some("killstreak_2_1200"), clips[0].label.as_deref());
+    assert_eq!((4500, 5300), (clips[1].start, clips[1].end));
+    assert_eq!(Some("bookmark_5000"), clips[1].label.as_deref());
+}
@@ -0,0 +1,265 @@
+use crate::wrapping::Wrapping;
+use crate::TickData;
+use serde::Deserialize;
+use splines::{Interpolate, Interpolation, Key, Spline};
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+use tf_demo_parser::demo::vector::Vector;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum InterpolationMode {
+    Step,
+    Linear,
+    Cosine,
+    CatmullRom,
+}
+
+impl FromStr for InterpolationMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "step" => Ok(InterpolationMode::Step),
+            "linear" => Ok(InterpolationMode::Linear),
+            "cosine" => Ok(InterpolationMode::Cosine),
+            "catmull-rom" | "catmullrom" => Ok(InterpolationMode::CatmullRom),
+            _ => Err(format!(
+                "unknown interpolation mode \"{}\", expected one of step, linear, cosine or catmull-rom",
+                s
+            )),
+        }
+    }
+}
+
+impl InterpolationMode {
+    fn interpolation<V>(self) -> Interpolation<f32, V> {
+        match self {
+            // hold the value of a tick until the next tick
+            InterpolationMode::Step => Interpolation::Step(1.0),
+            InterpolationMode::Linear => Interpolation::Linear,
+            InterpolationMode::Cosine => Interpolation::Cosine,
+            InterpolationMode::CatmullRom => Interpolation::CatmullRom,
+        }
+    }
+}
+
+/// Interpolation mode for every output channel
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct InterpolationConfig {
+    pub pitch: InterpolationMode,
+    pub yaw: InterpolationMode,
+    pub position: InterpolationMode,
+    pub ammo: InterpolationMode,
+    pub health: InterpolationMode,
+    pub uber: InterpolationMode,
+}
+
+impl Default for InterpolationConfig {
+    fn default() -> Self {
+        InterpolationConfig {
+            pitch: InterpolationMode::Cosine,
+            yaw: InterpolationMode::Cosine,
+            position: InterpolationMode::Linear,
+            ammo: InterpolationMode::Step,
+            health: InterpolationMode::Step,
+            uber: InterpolationMode::Step,
+        }
+    }
+}
+
+impl InterpolationConfig {
+    /// Load the interpolation modes from a json file, channels not in the file keep their default mode
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+        serde_json::from_str(&content)
+            .map_err(|e| format!("invalid interpolation config {}: {}", path.display(), e))
+    }
+
+    /// Apply a comma separated list of `channel=mode` pairs
+    pub fn apply(&mut self, spec: &str) -> Result<(), String> {
+        for pair in spec.split(',').filter(|pair| !pair.is_empty()) {
+            let (channel, mode) = pair
+                .split_once('=')
+                .ok_or_else(|| format!("expected channel=mode, got \"{}\"", pair))?;
+            let mode = mode.parse()?;
+            match channel.to_ascii_lowercase().as_str() {
+                "pitch" => self.pitch = mode,
+                "yaw" => self.yaw = mode,
+                "angles" => {
+                    self.pitch = mode;
+                    self.yaw = mode;
+                }
+                "position" => self.position = mode,
+                "ammo" => self.ammo = mode,
+                "health" => self.health = mode,
+                "uber" => self.uber = mode,
+                _ => return Err(format!("unknown channel \"{}\"", channel)),
+            }
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_apply_interpolation_spec() {
+    let mut config = InterpolationConfig::default();
+    config.apply("yaw=catmull-rom,health=linear").unwrap();
+    assert_eq!(InterpolationMode::Cosine, config.pitch);
+    assert_eq!(InterpolationMode::CatmullRom, config.yaw);
+    assert_eq!(InterpolationMode::Linear, config.health);
+
+    config.apply("angles=step").unwrap();
+    assert_eq!(InterpolationMode::Step, config.pitch);
+    assert_eq!(InterpolationMode::Step, config.yaw);
+
+    assert!(config.apply("ammo").is_err());
+    assert!(config.apply("ammo=smooth").is_err());
+    assert!(config.apply("armor=step").is_err());
+}
+
+/// A single output track, resampled from the per-tick values
+pub struct Channel<V> {
+    spline: Spline<f32, V>,
+}
+
+impl<V: Interpolate<f32>> Channel<V> {
+    pub fn new(points: impl IntoIterator<Item = (u32, V)>, mode: InterpolationMode) -> Self {
+        let interpolation = mode.interpolation();
+        let mut keys: Vec<_> = points
+            .into_iter()
+            .map(|(tick, value)| Key::new(tick as f32, value, interpolation))
+            .collect();
+
+        // catmull-rom needs a key before and after the pair that is being sampled,
+        // repeat the first and last keys so the edges of the channel can still be sampled
+        if mode == InterpolationMode::CatmullRom {
+            if let (Some(first), Some(last)) = (keys.first().copied(), keys.last().copied()) {
+                keys.insert(0, Key::new(first.t - 1.0, first.value, interpolation));
+                keys.push(Key::new(last.t + 1.0, last.value, interpolation));
+                keys.push(Key::new(last.t + 2.0, last.value, interpolation));
+            }
+        }
+
+        Channel {
+            spline: Spline::from_vec(keys),
+        }
+    }
+
+    /// Sample the channel at a (fractional) tick, ticks outside the channel get the value of the first or last key
+    pub fn sample(&self, tick: f32) -> Option<V> {
+        self.spline.clamped_sample(tick).or_else(|| {
+            let keys = self.spline.keys();
+            keys.iter()
+                .rev()
+                .find(|key| key.t <= tick)
+                .or_else(|| keys.first())
+                .map(|key| key.value)
+        })
+    }
+}
+
+#[test]
+fn test_channel_modes() {
+    let points = [(0, 0.0), (10, 10.0), (20, 30.0)];
+
+    let step = Channel::new(points, InterpolationMode::Step);
+    assert_eq!(Some(0.0), step.sample(5.0));
+    assert_eq!(Some(10.0), step.sample(19.0));
+    assert_eq!(Some(30.0), step.sample(25.0));
+
+    let linear = Channel::new(points, InterpolationMode::Linear);
+    assert_eq!(Some(5.0), linear.sample(5.0));
+    assert_eq!(Some(20.0), linear.sample(15.0));
+    assert_eq!(Some(0.0), linear.sample(-5.0));
+
+    let catmull_rom = Channel::new(points, InterpolationMode::CatmullRom);
+    assert_eq!(Some(0.0), catmull_rom.sample(0.0));
+    assert_eq!(Some(10.0), catmull_rom.sample(10.0));
+    assert_eq!(Some(30.0), catmull_rom.sample(20.0));
+    assert!(catmull_rom.sample(5.0).is_some());
+    assert!(catmull_rom.sample(15.0).is_some());
+
+    let empty = Channel::<f32>::new([], InterpolationMode::Linear);
+    assert_eq!(None, empty.sample(5.0));
+}
+
+/// All resampled channels of a recording
+pub struct Channels {
+    pub pitch: Channel<Wrapping<-180, 180>>,
+    pub yaw: Channel<Wrapping<-180, 180>>,
+    pub x: Channel<f32>,
+    pub y: Channel<f32>,
+    pub z: Channel<f32>,
+    pub ammo: Channel<f32>,
+    pub max_ammo: Channel<f32>,
+    pub health: Channel<f32>,
+    pub uber: Channel<f32>,
+}
+
+impl Channels {
+    pub fn new(state: &[TickData], config: &InterpolationConfig) -> Self {
+        Channels {
+            pitch: Channel::new(
+                state
+                    .iter()
+                    .filter_map(|data| Some((data.tick, Wrapping(data.angles[0]?)))),
+                config.pitch,
+            ),
+            yaw: Channel::new(
+                state
+                    .iter()
+                    .filter_map(|data| Some((data.tick, Wrapping(data.angles[1]?)))),
+                config.yaw,
+            ),
+            x: Channel::new(
+                state.iter().map(|data| (data.tick, data.position.x)),
+                config.position,
+            ),
+            y: Channel::new(
+                state.iter().map(|data| (data.tick, data.position.y)),
+                config.position,
+            ),
+            z: Channel::new(
+                state.iter().map(|data| (data.tick, data.position.z)),
+                config.position,
+            ),
+            ammo: Channel::new(
+                state.iter().map(|data| (data.tick, data.ammo as f32)),
+                config.ammo,
+            ),
+            max_ammo: Channel::new(
+                state.iter().map(|data| (data.tick, data.max_ammo as f32)),
+                config.ammo,
+            ),
+            health: Channel::new(
+                state.iter().map(|data| (data.tick, data.health as f32)),
+                config.health,
+            ),
+            uber: Channel::new(
+                state
+                    .iter()
+                    .filter_map(|data| Some((data.tick, data.uber? as f32))),
+                config.uber,
+            ),
+        }
+    }
+
+    /// Pitch and yaw at a tick, `None` if the player never had their view angles set
+    pub fn angles(&self, tick: f32) -> Option<[f32; 2]> {
+        Some([self.pitch.sample(tick)?.0, self.yaw.sample(tick)?.0])
+    }
+
+    /// Position at a tick, `None` if there are no ticks for the player
+    pub fn position(&self, tick: f32) -> Option<Vector> {
+        Some(Vector {
+            x: self.x.sample(tick)?,
+            y: self.y.sample(tick)?,
+            z: self.z.sample(tick)?,
+        })
+    }
+}
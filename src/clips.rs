@@ -0,0 +1,170 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// A single clip to extract from a demo
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Clip {
+    /// Name or steam id of the player, `None` for the player that recorded the demo
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub player: Option<String>,
+    pub start: u32,
+    pub end: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+}
+
+impl Clip {
+    /// Name for the output of the clip, the label if set, otherwise made from the player and tick range
+    pub fn name(&self) -> String {
+        let name = match (&self.label, &self.player) {
+            (Some(label), _) => label.clone(),
+            (None, Some(player)) => format!("{}_{}-{}", player, self.start, self.end),
+            (None, None) => format!("{}-{}", self.start, self.end),
+        };
+        name.chars()
+            .map(|c| {
+                if c.is_alphanumeric() || c == '-' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect()
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+struct ClipFile {
+    clips: Vec<Clip>,
+}
+
+/// Load a list of clips from a toml file, or a csv file if it has a `.csv` extension
+///
+/// ```toml
+/// [[clips]]
+/// player = "name or steam id"
+/// start = 1000
+/// end = 2000
+/// label = "airshot"
+/// ```
+///
+/// Csv files have a `player,start,end,label` header, the player and label can be left empty.
+pub fn load_clips(path: impl AsRef<Path>) -> Result<Vec<Clip>, String> {
+    let path = path.as_ref();
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("failed to read clip list {}: {}", path.display(), e))?;
+    let clips = if path.extension() == Some("csv".as_ref()) {
+        clips_from_csv(&content)
+    } else {
+        clips_from_toml(&content)
+    }
+    .map_err(|e| format!("invalid clip list {}: {}", path.display(), e))?;
+
+    for clip in &clips {
+        if clip.end < clip.start {
+            return Err(format!(
+                "invalid clip list {}: end tick {} is before start tick {}",
+                path.display(),
+                clip.end,
+                clip.start
+            ));
+        }
+    }
+    Ok(clips)
+}
+
+/// Write a list of clips in the format read by [`load_clips`], csv if the path has a `.csv` extension, toml otherwise
+pub fn save_clips(path: impl AsRef<Path>, clips: &[Clip]) -> Result<(), String> {
+    let path = path.as_ref();
+    let content = if path.extension() == Some("csv".as_ref()) {
+        clips_to_csv(clips)
+    } else {
+        toml::to_string(&ClipFile {
+            clips: clips.to_vec(),
+        })
+        .map_err(|e| e.to_string())
+    }
+    .map_err(|e| format!("failed to write clip list {}: {}", path.display(), e))?;
+    fs::write(path, content)
+        .map_err(|e| format!("failed to write clip list {}: {}", path.display(), e))
+}
+
+fn clips_to_csv(clips: &[Clip]) -> Result<String, String> {
+    let mut writer = ::csv::Writer::from_writer(Vec::new());
+    writer
+        .write_record(["player", "start", "end", "label"])
+        .map_err(|e| e.to_string())?;
+    for clip in clips {
+        writer
+            .write_record(&[
+                clip.player.clone().unwrap_or_default(),
+                clip.start.to_string(),
+                clip.end.to_string(),
+                clip.label.clone().unwrap_or_default(),
+            ])
+            .map_err(|e| e.to_string())?;
+    }
+    let content = writer.into_inner().map_err(|e| e.to_string())?;
+    String::from_utf8(content).map_err(|e| e.to_string())
+}
+
+pub fn clips_from_csv(content: &str) -> Result<Vec<Clip>, String> {
+    ::csv::ReaderBuilder::new()
+        .trim(::csv::Trim::All)
+        .from_reader(content.as_bytes())
+        .deserialize()
+        .collect::<Result<_, _>>()
+        .map_err(|e| e.to_string())
+}
+
+pub fn clips_from_toml(content: &str) -> Result<Vec<Clip>, String> {
+    toml::from_str::<ClipFile>(content)
+        .map(|file| file.clips)
+        .map_err(|e| e.to_string())
+}
+
+#[test]
+fn test_load_clips() {
+    let expected = vec![
+        Clip {
+            player: Some("bob".to_string()),
+            start: 1000,
+            end: 2000,
+            label: Some("airshot".to_string()),
+        },
+        Clip {
+            player: None,
+            start: 3000,
+            end: 3500,
+            label: None,
+        },
+    ];
+
+    let csv = "player,start,end,label\nbob, 1000, 2000, airshot\n,3000,3500,\n";
+    assert_eq!(Ok(expected.clone()), clips_from_csv(csv));
+
+    let toml = r#"
+        [[clips]]
+        player = "bob"
+        start = 1000
+        end = 2000
+        label = "airshot"
+
+        [[clips]]
+        start = 3000
+        end = 3500
+    "#;
+    assert_eq!(Ok(expected.clone()), clips_from_toml(toml));
+
+    let csv = clips_to_csv(&expected).unwrap();
+    assert_eq!(Ok(expected.clone()), clips_from_csv(&csv));
+    let toml = toml::to_string(&ClipFile {
+        clips: expected.clone(),
+    })
+    .unwrap();
+    assert_eq!(Ok(expected.clone()), clips_from_toml(&toml));
+
+    assert_eq!("airshot", expected[0].name());
+    assert_eq!("3000-3500", expected[1].name());
+}
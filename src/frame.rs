@@ -0,0 +1,301 @@
+use crate::analyser::weapon_name;
+use crate::channel::Channels;
+use crate::multikill::MultiKillTracker;
+use crate::timing::Timing;
+use crate::TickData;
+use cgmath::{Deg, Euler, Matrix3, Quaternion};
+use serde::Serialize;
+use tf_demo_parser::demo::vector::Vector;
+
+/// Number of ticks over which the damage number of a hit fades out
+const HIT_TIME: f32 = 33.0;
+/// Default time after the first hit of a batch in which hits are added to it, tf2's default `hud_combattext_batching_window`
+pub const DEFAULT_DAMAGE_BATCH_WINDOW: f32 = 0.2;
+/// Height above the victim's origin at which the damage numbers are shown
+const DAMAGE_NUMBER_HEIGHT: f32 = 80.0;
+const ASPECT_RATIO: f32 = 16.0 / 9.0;
+/// tf2's default fov in degrees, which is defined horizontally for a 4:3 aspect ratio
+pub const DEFAULT_FOV: f32 = 90.0;
+
+/// The resampled data for a single output frame
+#[derive(Clone, Debug, Serialize)]
+pub struct Frame {
+    pub frame: i32,
+    pub tick: f32,
+    pub ammo: u16,
+    pub max_ammo: u16,
+    pub health: u16,
+    pub uber: Option<u8>,
+    pub weapon: String,
+    pub hit: u32,
+    /// Whether the damage shown by `hit` was dealt to an airborne enemy
+    pub airshot: bool,
+    /// Weapon that dealt the damage shown by `hit`
+    pub hit_weapon: String,
+    /// Kills since the player last died
+    pub life_kills: u16,
+    /// Kills since the start of the clip
+    pub clip_kills: u16,
+    /// Number of kills in the multi kill that's going on, `0` when there is none
+    pub multi_kill: u16,
+    /// Every hit that's still shown as a floating damage number
+    pub damage_numbers: Vec<DamageNumber>,
+    /// Total damage of the hits that happened within the batching window, fading out like `hit`
+    pub batched_hit: u32,
+    /// Damage dealt since the player last died
+    pub life_damage: u32,
+    /// Absolute view position
+    pub position: Vector,
+    /// Absolute pitch and yaw
+    pub angles: [f32; 2],
+    /// Change in pitch and yaw since the previous frame
+    pub delta_angles: [f32; 2],
+    pub camera: CameraOut,
+}
+
+/// Damage number of a single hit, shown above the damaged player
+#[derive(Clone, Copy, Debug, Serialize)]
+pub struct DamageNumber {
+    pub damage: u32,
+    /// Position on screen from `[0, 0]` (top left) to `[1, 1]` (bottom right) for a 16:9 view,
+    /// `None` if the position of the damaged player is unknown or behind the view
+    pub screen: Option<[f32; 2]>,
+    /// How far the number has faded out, from `0` when the hit happens to `1`
+    pub age: f32,
+}
+
+/// Camera movement relative to the start of the clip
+#[derive(Clone, Copy, Debug, Serialize)]
+pub struct CameraOut {
+    pub position: Vector,
+    pub angle: [f32; 3],
+}
+
+/// Resample the per-tick data for the `[start, end]` tick range into output frames
+///
+/// `multi_kill_window` is the maximum number of ticks between two kills of a multi kill,
+/// `damage_batch_window` the number of ticks after the first hit of a batch in which hits are added to the batch.
+pub fn build_frames(
+    state: &[TickData],
+    channels: &Channels,
+    timing: &Timing,
+    start: u32,
+    end: u32,
+    multi_kill_window: u32,
+    damage_batch_window: u32,
+) -> Vec<Frame> {
+    let mut frames = Vec::new();
+    let mut last_frame = 0;
+    let mut last_angles: Option<[f32; 2]> = None;
+
+    let mut hit_last_damage: u32 = 0;
+    let mut hit_last_tick: u32 = 0;
+    let mut hit_last_airshot = false;
+    let mut hit_last_weapon = String::new();
+    // tick, damage and victim position of every hit that's still shown
+    let mut shown_hits: Vec<(u32, u32, Option<Vector>)> = Vec::new();
+    let mut batch_start: u32 = 0;
+    let mut batch_last: u32 = 0;
+    let mut batch_damage: u32 = 0;
+    let mut life_damage: u32 = 0;
+    let mut life_kills: u16 = 0;
+    let mut clip_kills: u16 = 0;
+    let mut multi_kill = MultiKillTracker::new(multi_kill_window);
+
+    // players without any ticks, like spectators, have nothing to resample
+    let (start_position, start_angles) = match (
+        channels.position(start as f32),
+        channels.angles(start as f32),
+    ) {
+        (Some(position), Some(angles)) => (position, angles),
+        _ => return frames,
+    };
+
+    for data in state
+        .iter()
+        .filter(|data| data.tick >= start && data.tick <= end)
+    {
+        let frame = timing.frame_for_tick(data.tick);
+
+        if let Some(hit) = data.hit {
+            hit_last_damage = hit;
+            hit_last_tick = data.tick;
+            hit_last_airshot = data.airshot;
+            hit_last_weapon = data.hit_weapon.map(weapon_name).unwrap_or_default();
+        }
+
+        shown_hits.retain(|(tick, _, _)| (data.tick - tick) as f32 <= HIT_TIME);
+        shown_hits.extend(
+            data.hits
+                .iter()
+                .map(|hit| (data.tick, hit.damage, hit.victim_position)),
+        );
+        let damage: u32 = data.hits.iter().map(|hit| hit.damage).sum();
+        if damage > 0 {
+            if batch_damage > 0 && data.tick <= batch_start + damage_batch_window {
+                batch_damage += damage;
+            } else {
+                batch_damage = damage;
+                batch_start = data.tick;
+            }
+            batch_last = data.tick;
+        }
+
+        for frame in last_frame..frame {
+            let tick = timing.tick_for_frame(frame);
+            let hit_age = (tick - hit_last_tick as f32).max(0.0);
+            let hit_ratio = ((HIT_TIME - hit_age) / HIT_TIME).max(0.0);
+            let hit_number = hit_last_damage as f32 * hit_ratio;
+            let batch_age = (tick - batch_last as f32).max(0.0);
+            let batch_ratio = ((HIT_TIME - batch_age) / HIT_TIME).max(0.0);
+            // the channels aren't empty, so they can be sampled at any tick
+            let angles = channels.angles(tick).unwrap_or(start_angles);
+            let position = channels.position(tick).unwrap_or(start_position);
+            let mut delta_angles = match last_angles {
+                Some(last_angles) => [angles[0] - last_angles[0], angles[1] - last_angles[1]],
+                None => [0.0, 0.0],
+            };
+
+            if delta_angles[1] < -180.0 {
+                delta_angles[1] += 360.0;
+            }
+            if delta_angles[1] > 180.0 {
+                delta_angles[1] -= 360.0;
+            }
+
+            let yaw_matrix = <Matrix3<f32>>::from_angle_y(degrees(-(angles[1] - start_angles[1])));
+            let pitch_matrix =
+                <Matrix3<f32>>::from_angle_x(degrees(-(angles[0] - start_angles[0])));
+            let quat = <Quaternion<f32>>::from(yaw_matrix * pitch_matrix);
+            let euler = Euler::from(quat);
+
+            frames.push(Frame {
+                frame,
+                tick,
+                ammo: channels.ammo.sample(tick).unwrap_or_default().round() as u16,
+                max_ammo: channels.max_ammo.sample(tick).unwrap_or_default().round() as u16,
+                health: channels.health.sample(tick).unwrap_or_default().round() as u16,
+                uber: channels.uber.sample(tick).map(|uber| uber.round() as u8),
+                weapon: data.weapon.clone(),
+                hit: hit_number as u32,
+                airshot: hit_last_airshot && hit_ratio > 0.0,
+                hit_weapon: if hit_ratio > 0.0 {
+                    hit_last_weapon.clone()
+                } else {
+                    String::new()
+                },
+                life_kills,
+                clip_kills,
+                multi_kill: multi_kill.active(tick),
+                damage_numbers: shown_hits
+                    .iter()
+                    .map(|(hit_tick, damage, victim)| DamageNumber {
+                        damage: *damage,
+                        screen: victim.and_then(|victim| {
+                            let mut above = victim;
+                            above.z += DAMAGE_NUMBER_HEIGHT;
+                            screen_position(position, angles, above)
+                        }),
+                        age: ((tick - *hit_tick as f32).max(0.0) / HIT_TIME).min(1.0),
+                    })
+                    .filter(|number| number.age < 1.0)
+                    .collect(),
+                batched_hit: (batch_damage as f32 * batch_ratio) as u32,
+                life_damage,
+                position,
+                angles,
+                delta_angles,
+                camera: CameraOut {
+                    position: position - start_position,
+                    angle: [
+                        Deg::from(euler.x).0,
+                        Deg::from(euler.y).0,
+                        Deg::from(euler.z).0,
+                    ],
+                },
+            });
+            last_angles = Some(angles);
+        }
+        last_frame = frame;
+
+        // the kills are only counted in the frames at or after the tick they happen
+        life_kills = data.life_kills;
+        life_damage = data.life_damage;
+        clip_kills += data.kills;
+        multi_kill.push(data.tick, data.kills);
+    }
+    frames
+}
+
+/// Express a world space offset in the axes of a view as `[forward, right, up]`
+pub fn view_offset(angles: [f32; 2], delta: Vector) -> [f32; 3] {
+    let (pitch_sin, pitch_cos) = angles[0].to_radians().sin_cos();
+    let (yaw_sin, yaw_cos) = angles[1].to_radians().sin_cos();
+    // source's view vectors, a positive pitch looks down
+    let forward = [pitch_cos * yaw_cos, pitch_cos * yaw_sin, -pitch_sin];
+    let right = [yaw_sin, -yaw_cos, 0.0];
+    let up = [pitch_sin * yaw_cos, pitch_sin * yaw_sin, pitch_cos];
+
+    let dot = |axis: [f32; 3]| delta.x * axis[0] + delta.y * axis[1] + delta.z * axis[2];
+    [dot(forward), dot(right), dot(up)]
+}
+
+/// Tangent of half of the vertical fov matching tf2's default fov, the vertical fov doesn't depend on the aspect ratio
+pub(crate) fn half_fov_tan_vertical() -> f32 {
+    (DEFAULT_FOV / 2.0).to_radians().tan() * 3.0 / 4.0
+}
+
+/// Project a point in the world onto the screen of a view with tf2's default fov
+fn screen_position(view: Vector, angles: [f32; 2], target: Vector) -> Option<[f32; 2]> {
+    let [depth, right, up] = view_offset(angles, target - view);
+    if depth <= 0.0 {
+        return None;
+    }
+    let half_tan_vertical = half_fov_tan_vertical();
+    let half_tan_horizontal = half_tan_vertical * ASPECT_RATIO;
+    Some([
+        0.5 + right / depth / (2.0 * half_tan_horizontal),
+        0.5 - up / depth / (2.0 * half_tan_vertical),
+    ])
+}
+
+#[test]
+fn test_screen_position() {
+    let view = Vector {
+        x: 0.0,
+        y: 0.0,
+        z: 0.0,
+    };
+    let point = |x, y, z| Vector { x, y, z };
+
+    assert_eq!(
+        Some([0.5, 0.5]),
+        screen_position(view, [0.0, 0.0], point(100.0, 0.0, 0.0))
+    );
+    assert_eq!(
+        None,
+        screen_position(view, [0.0, 0.0], point(-100.0, 0.0, 0.0))
+    );
+    // looking along the y axis, points to the left (+x is to the right) are on the left half
+    let [x, y] = screen_position(view, [0.0, 90.0], point(-50.0, 100.0, 0.0)).unwrap();
+    assert!(x < 0.5);
+    assert!((y - 0.5).abs() < 0.001);
+    // the top edge of the fov is at the top of the screen
+    let [_, y] = screen_position(view, [0.0, 0.0], point(100.0, 0.0, 75.0)).unwrap();
+    assert!(y.abs() < 0.001);
+}
+
+#[test]
+fn test_build_frames_without_ticks() {
+    use crate::channel::InterpolationConfig;
+    use crate::timing::FrameRate;
+
+    let player = crate::PlayerTicks::default();
+    let channels = Channels::new(&player.ticks, &InterpolationConfig::default());
+    let timing = Timing::new(1000, 0.015, FrameRate::new(60, 1));
+    assert!(build_frames(&player.ticks, &channels, &timing, 1000, 2000, 200, 13).is_empty());
+}
+
+pub const fn degrees(v: f32) -> Deg<f32> {
+    Deg(v)
+}
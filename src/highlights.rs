@@ -0,0 +1,247 @@
+use crate::clips::Clip;
+use crate::multikill::{find_multi_kills, DEFAULT_MULTI_KILL_WINDOW};
+use crate::TickData;
+use serde::Serialize;
+
+/// Settings for detecting highlights, times are in seconds
+#[derive(Clone, Debug)]
+pub struct HighlightOptions {
+    /// Maximum time between two kills of a multi kill
+    pub multi_kill_window: f32,
+    /// Maximum time between two hits of a damage burst
+    pub burst_gap: f32,
+    /// Minimum total damage of a damage burst
+    pub burst_damage: u32,
+    /// Maximum health of the player for a kill to count as a low health kill
+    pub low_health: u16,
+}
+
+impl Default for HighlightOptions {
+    fn default() -> Self {
+        HighlightOptions {
+            multi_kill_window: DEFAULT_MULTI_KILL_WINDOW,
+            burst_gap: 1.5,
+            burst_damage: 300,
+            low_health: 40,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum HighlightKind {
+    MultiKill {
+        kills: u16,
+    },
+    /// Damage dealt to an airborne enemy
+    Airshot {
+        damage: u32,
+    },
+    /// Lots of damage dealt in quick succession
+    DamageBurst {
+        damage: u32,
+    },
+    /// Kill made with the player's health at or below the low health threshold
+    LowHealthKill {
+        health: u16,
+    },
+    /// Kill on a medic with a full uber
+    UberDrop,
+}
+
+/// A moment in the demo that might be worth clipping
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct Highlight {
+    #[serde(flatten)]
+    pub kind: HighlightKind,
+    pub start_tick: u32,
+    pub end_tick: u32,
+    /// How interesting the highlight is, higher is better
+    pub score: u32,
+}
+
+impl Highlight {
+    fn new(kind: HighlightKind, start_tick: u32, end_tick: u32) -> Self {
+        let score = match kind {
+            HighlightKind::MultiKill { kills } => kills as u32 * kills as u32 * 10,
+            HighlightKind::Airshot { damage } => 40 + damage / 10,
+            HighlightKind::DamageBurst { damage } => damage / 10,
+            HighlightKind::LowHealthKill { health } => 30 + 40u32.saturating_sub(health as u32),
+            HighlightKind::UberDrop => 60,
+        };
+        Highlight {
+            kind,
+            start_tick,
+            end_tick,
+            score,
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match self.kind {
+            HighlightKind::MultiKill { kills: 2 } => "double kill".to_string(),
+            HighlightKind::MultiKill { kills: 3 } => "triple kill".to_string(),
+            HighlightKind::MultiKill { kills: 4 } => "quad kill".to_string(),
+            HighlightKind::MultiKill { kills } => format!("{} kills", kills),
+            HighlightKind::Airshot { damage } => format!("{} damage airshot", damage),
+            HighlightKind::DamageBurst { damage } => format!("{} damage burst", damage),
+            HighlightKind::LowHealthKill { health } => format!("kill at {} health", health),
+            HighlightKind::UberDrop => "forced uber drop".to_string(),
+        }
+    }
+
+    /// A clip of the highlight, starting `pre_roll` ticks before and ending `post_roll` ticks after it
+    pub fn clip(&self, player: Option<String>, pre_roll: u32, post_roll: u32) -> Clip {
+        Clip {
+            player,
+            start: self.start_tick.saturating_sub(pre_roll),
+            end: self.end_tick.saturating_add(post_roll),
+            label: Some(format!("{}_{}", self.name(), self.start_tick)),
+        }
+    }
+}
+
+/// Find the highlights in the per-tick data of a player, ordered by tick
+pub fn find_highlights(
+    ticks: &[TickData],
+    options: &HighlightOptions,
+    time_per_tick: f32,
+) -> Vec<Highlight> {
+    let to_ticks = |seconds: f32| (seconds / time_per_tick).round() as u32;
+    // only damage dealt to enemies, `hit` also contains self damage
+    let damage = |data: &TickData| data.hits.iter().map(|hit| hit.damage).sum::<u32>();
+    let mut highlights: Vec<Highlight> =
+        find_multi_kills(ticks, to_ticks(options.multi_kill_window))
+            .into_iter()
+            .map(|multi_kill| {
+                Highlight::new(
+                    HighlightKind::MultiKill {
+                        kills: multi_kill.kills,
+                    },
+                    multi_kill.start_tick,
+                    multi_kill.end_tick,
+                )
+            })
+            .collect();
+
+    for data in ticks {
+        if data.airshot {
+            highlights.push(Highlight::new(
+                HighlightKind::Airshot {
+                    damage: damage(data),
+                },
+                data.tick,
+                data.tick,
+            ));
+        }
+        if data.kills > 0 && data.health > 0 && data.health <= options.low_health {
+            highlights.push(Highlight::new(
+                HighlightKind::LowHealthKill {
+                    health: data.health,
+                },
+                data.tick,
+                data.tick,
+            ));
+        }
+        for _ in 0..data.uber_drops {
+            highlights.push(Highlight::new(
+                HighlightKind::UberDrop,
+                data.tick,
+                data.tick,
+            ));
+        }
+    }
+
+    // hits with at most the burst gap between them
+    let burst_gap = to_ticks(options.burst_gap);
+    let mut burst: Option<(u32, u32, u32)> = None;
+    let finish_burst = |burst: (u32, u32, u32), highlights: &mut Vec<Highlight>| {
+        let (start, end, damage) = burst;
+        if damage >= options.burst_damage {
+            highlights.push(Highlight::new(
+                HighlightKind::DamageBurst { damage },
+                start,
+                end,
+            ));
+        }
+    };
+    for data in ticks {
+        let hit = damage(data);
+        if hit == 0 {
+            continue;
+        }
+        burst = match burst {
+            Some((start, end, damage)) if data.tick <= end + burst_gap => {
+                Some((start, data.tick, damage + hit))
+            }
+            Some(finished) => {
+                finish_burst(finished, &mut highlights);
+                Some((data.tick, data.tick, hit))
+            }
+            None => Some((data.tick, data.tick, hit)),
+        };
+    }
+    if let Some(finished) = burst {
+        finish_burst(finished, &mut highlights);
+    }
+
+    highlights.sort_by_key(|highlight| (highlight.start_tick, highlight.end_tick));
+    highlights
+}
+
+#[test]
+fn test_find_highlights() {
+    let tick = |tick, hit, kills, health| TickData {
+        tick,
+        ammo: 0,
+        max_ammo: 0,
+        health,
+        uber: None,
+        angles: [None, None],
+        hit,
+        hit_weapon: None,
+        weapon: String::new(),
+        position: Default::default(),
+        kills,
+        life_kills: 0,
+        airshot: false,
+        uber_drops: 0,
+        hits: hit
+            .map(|damage| crate::Hit {
+                damage,
+                weapon_id: 22,
+                victim_position: None,
+            })
+            .into_iter()
+            .collect(),
+        life_damage: 0,
+    };
+    let mut airshot = tick(240, Some(110), 0, 200);
+    airshot.airshot = true;
+    let mut ticks = vec![
+        tick(100, Some(90), 1, 200),
+        tick(150, Some(100), 1, 30),
+        airshot,
+        tick(1000, Some(50), 0, 200),
+    ];
+    // self damage only sets `hit` and doesn't continue the burst of the hit before
+    let mut rocket_jump = tick(1100, Some(300), 0, 200);
+    rocket_jump.hits.clear();
+    ticks.push(rocket_jump);
+    let highlights = find_highlights(&ticks, &HighlightOptions::default(), 0.015);
+
+    assert_eq!(
+        vec![
+            Highlight::new(HighlightKind::MultiKill { kills: 2 }, 100, 150),
+            Highlight::new(HighlightKind::DamageBurst { damage: 300 }, 100, 240),
+            Highlight::new(HighlightKind::LowHealthKill { health: 30 }, 150, 150),
+            Highlight::new(HighlightKind::Airshot { damage: 110 }, 240, 240),
+        ],
+        highlights
+    );
+    assert_eq!(40, highlights[0].score);
+    assert_eq!(
+        "double kill_100",
+        highlights[0].clip(None, 0, 0).label.unwrap()
+    );
+}
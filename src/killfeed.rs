@@ -0,0 +1,154 @@
+use crate::timing::Timing;
+use serde::Serialize;
+use tf_demo_parser::demo::parser::gamestateanalyser::Team;
+
+/// How long a kill stays in the kill feed, tf2's default `hud_deathnotice_time`
+const DISPLAY_TIME: f64 = 6.0;
+
+/// `DMG_CRITICAL` damage bit
+const DAMAGE_CRITICAL: u32 = 1 << 20;
+/// `crit_type` of a mini crit
+const CRIT_TYPE_MINI: u16 = 1;
+
+const CUSTOM_KILL_HEADSHOT: u16 = 1;
+const CUSTOM_KILL_BACKSTAB: u16 = 2;
+const CUSTOM_KILL_PENETRATE_HEADSHOT: u16 = 14;
+const CUSTOM_KILL_HEADSHOT_DECAPITATION: u16 = 51;
+
+/// A player as shown in the kill feed
+#[derive(Clone, Debug, Serialize)]
+pub struct KillFeedPlayer {
+    pub name: String,
+    pub team: Team,
+    /// Team colour as hex color, like the names in the hud kill feed
+    pub color: &'static str,
+}
+
+impl KillFeedPlayer {
+    pub fn new(name: String, team: Team) -> Self {
+        let color = match team {
+            Team::Red => "#B8383B",
+            Team::Blue => "#5885A2",
+            _ => "#F2EFDC",
+        };
+        KillFeedPlayer { name, team, color }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Crit {
+    None,
+    Mini,
+    Full,
+}
+
+impl Crit {
+    pub fn new(damage_bits: u32, crit_type: u16) -> Self {
+        if damage_bits & DAMAGE_CRITICAL != 0 {
+            Crit::Full
+        } else if crit_type == CRIT_TYPE_MINI {
+            Crit::Mini
+        } else {
+            Crit::None
+        }
+    }
+}
+
+/// Special kill types that change the kill icon
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KillType {
+    Normal,
+    Headshot,
+    Backstab,
+    /// Any other custom kill, like taunt kills or telefrags
+    Other,
+}
+
+impl KillType {
+    pub fn new(custom_kill: u16) -> Self {
+        match custom_kill {
+            0 => KillType::Normal,
+            CUSTOM_KILL_HEADSHOT
+            | CUSTOM_KILL_PENETRATE_HEADSHOT
+            | CUSTOM_KILL_HEADSHOT_DECAPITATION => KillType::Headshot,
+            CUSTOM_KILL_BACKSTAB => KillType::Backstab,
+            _ => KillType::Other,
+        }
+    }
+}
+
+/// A single entry in the kill feed
+#[derive(Clone, Debug, Serialize)]
+pub struct Kill {
+    pub tick: u32,
+    /// `None` for suicides and kills by the world
+    pub attacker: Option<KillFeedPlayer>,
+    pub victim: KillFeedPlayer,
+    pub assister: Option<KillFeedPlayer>,
+    /// Kill icon name
+    pub weapon: String,
+    pub crit: Crit,
+    pub kill_type: KillType,
+}
+
+/// A kill with the frames it's shown in the kill feed
+#[derive(Clone, Debug, Serialize)]
+pub struct KillFeedEntry {
+    #[serde(flatten)]
+    pub kill: Kill,
+    /// First frame the kill is shown, negative if the kill happened before the start of the clip
+    pub appear: i32,
+    /// First frame the kill is no longer shown
+    pub expire: i32,
+}
+
+/// The kills shown in the kill feed at some point during the `[start, end]` tick range
+pub fn build_kill_feed(
+    kills: &[Kill],
+    timing: &Timing,
+    start: u32,
+    end: u32,
+) -> Vec<KillFeedEntry> {
+    let display_frames = (DISPLAY_TIME * timing.frame_rate().fps()).round() as i32;
+    let start_frame = timing.frame_for_tick(start);
+    kills
+        .iter()
+        .filter(|kill| kill.tick <= end)
+        .map(|kill| {
+            let appear = timing.frame_for_tick(kill.tick);
+            KillFeedEntry {
+                kill: kill.clone(),
+                appear,
+                expire: appear + display_frames,
+            }
+        })
+        .filter(|entry| entry.expire > start_frame)
+        .collect()
+}
+
+#[test]
+fn test_kill_feed_range() {
+    use crate::timing::FrameRate;
+
+    let kill = |tick| Kill {
+        tick,
+        attacker: Some(KillFeedPlayer::new("attacker".to_string(), Team::Red)),
+        victim: KillFeedPlayer::new("victim".to_string(), Team::Blue),
+        assister: None,
+        weapon: "tf_projectile_rocket".to_string(),
+        crit: Crit::new(1 << 20, 0),
+        kill_type: KillType::new(0),
+    };
+    // 6 seconds is 400 ticks
+    let kills = vec![kill(500), kill(700), kill(1500), kill(2100)];
+    let timing = Timing::new(1000, 0.015, FrameRate::new(60, 1));
+    let kill_feed = build_kill_feed(&kills, &timing, 1000, 2000);
+
+    assert_eq!(2, kill_feed.len());
+    assert_eq!(700, kill_feed[0].kill.tick);
+    assert_eq!((-270, 90), (kill_feed[0].appear, kill_feed[0].expire));
+    assert_eq!((450, 810), (kill_feed[1].appear, kill_feed[1].expire));
+    assert_eq!(Crit::Full, kill_feed[1].kill.crit);
+}
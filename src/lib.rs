@@ -0,0 +1,294 @@
+mod analyser;
+pub mod bookmarks;
+pub mod channel;
+pub mod clips;
+pub mod frame;
+pub mod highlights;
+pub mod killfeed;
+pub mod lookup;
+pub mod multikill;
+pub mod output;
+mod players;
+mod playersearch;
+pub mod timing;
+mod wrapping;
+
+pub use crate::analyser::{AmmoCountAnalyser, Errors, Hit, PlayerSelection, PlayerTicks, TickData};
+pub use crate::bookmarks::{bookmark_clips, find_bookmark_file, load_bookmarks, Bookmark};
+pub use crate::channel::{InterpolationConfig, InterpolationMode};
+pub use crate::clips::{load_clips, save_clips, Clip};
+pub use crate::frame::{DamageNumber, Frame, DEFAULT_DAMAGE_BATCH_WINDOW};
+pub use crate::highlights::{find_highlights, Highlight, HighlightOptions};
+pub use crate::killfeed::{Kill, KillFeedEntry};
+pub use crate::lookup::{Aliases, LookupOptions};
+pub use crate::multikill::{MultiKill, MultiKillMarker};
+pub use crate::players::{list_players, PlayerInfo};
+pub use crate::playersearch::PlayerResolver;
+pub use crate::timing::{FrameRate, Timing};
+pub use tf_demo_parser::demo::header::Header;
+
+use crate::channel::Channels;
+use crate::frame::build_frames;
+use crate::killfeed::build_kill_feed;
+use crate::multikill::{multi_kill_markers, DEFAULT_MULTI_KILL_WINDOW};
+use crate::playersearch::get_player;
+use std::ops::RangeInclusive;
+use tf_demo_parser::demo::message::packetentities::EntityId;
+use tf_demo_parser::demo::parser::analyser::UserId;
+use tf_demo_parser::{Demo, DemoParser, ParseError};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("failed to parse demo: {0}")]
+    Parse(#[from] ParseError),
+    #[error("no server info or player not found")]
+    PlayerNotFound,
+    #[error("failed to search demos.tf: {0}")]
+    Lookup(String),
+    #[error("multiple players match, pick one of: {}", .candidates.join(", "))]
+    AmbiguousPlayer { candidates: Vec<String> },
+    #[error("can't pick player {}, pick one of: {}", .pick, .candidates.join(", "))]
+    PickOutOfRange {
+        pick: usize,
+        candidates: Vec<String>,
+    },
+}
+
+/// Settings for finding the player and resampling the extracted data into frames
+#[derive(Clone, Debug)]
+pub struct ExtractOptions {
+    pub frame_rate: FrameRate,
+    pub interpolation: InterpolationConfig,
+    pub lookup: LookupOptions,
+    /// Maximum time between two kills of a multi kill, in seconds
+    pub multi_kill_window: f32,
+    /// Time after the first hit of a batch in which hits are added to the batched damage number, in seconds
+    pub damage_batch_window: f32,
+}
+
+impl Default for ExtractOptions {
+    fn default() -> Self {
+        ExtractOptions {
+            frame_rate: FrameRate::default(),
+            interpolation: InterpolationConfig::default(),
+            lookup: LookupOptions::default(),
+            multi_kill_window: DEFAULT_MULTI_KILL_WINDOW,
+            damage_batch_window: DEFAULT_DAMAGE_BATCH_WINDOW,
+        }
+    }
+}
+
+/// The data extracted from a demo for a single clip
+pub struct Extraction {
+    pub header: Header,
+    /// Per-tick data within the requested tick range
+    pub ticks: Vec<TickData>,
+    /// Resampled frames for the requested tick range
+    pub frames: Vec<Frame>,
+    /// Kills shown in the kill feed during the requested tick range
+    pub kill_feed: Vec<KillFeedEntry>,
+    /// Multi kills made by the player that end within the requested tick range
+    pub multi_kills: Vec<MultiKillMarker>,
+    pub timing: Timing,
+    pub errors: Errors,
+    /// Name of the player
+    pub player: String,
+    /// SteamID3 of the player, `None` for bots
+    pub steam_id: Option<String>,
+    /// The requested tick range
+    pub range: RangeInclusive<u32>,
+}
+
+impl Extraction {
+    fn new(
+        header: Header,
+        player: &PlayerTicks,
+        range: RangeInclusive<u32>,
+        options: &ExtractOptions,
+    ) -> Self {
+        let (start, end) = (*range.start(), *range.end());
+        let time_per_tick = header.duration / header.ticks as f32;
+        let timing = Timing::new(start, time_per_tick, options.frame_rate);
+        let channels = Channels::new(&player.ticks, &options.interpolation);
+        let multi_kill_window = (options.multi_kill_window / time_per_tick).round() as u32;
+        let damage_batch_window = (options.damage_batch_window / time_per_tick).round() as u32;
+        let frames = build_frames(
+            &player.ticks,
+            &channels,
+            &timing,
+            start,
+            end,
+            multi_kill_window,
+            damage_batch_window,
+        );
+        let kill_feed = build_kill_feed(&player.kills, &timing, start, end);
+        let multi_kills = multi_kill_markers(&player.ticks, multi_kill_window, &timing, start, end);
+
+        // only the clip's ticks are kept, so extracting many clips doesn't hold a copy of the entire demo per clip
+        let first = player.ticks.partition_point(|data| data.tick < start);
+        let last = player.ticks.partition_point(|data| data.tick <= end);
+
+        Extraction {
+            header,
+            ticks: player.ticks[first..last].to_vec(),
+            frames,
+            kill_feed,
+            multi_kills,
+            timing,
+            errors: player.errors.clone(),
+            player: player.name.clone(),
+            steam_id: player.steam_id.clone(),
+            range,
+        }
+    }
+}
+
+/// Read only the header of the demo
+pub fn read_header(demo: &[u8]) -> Result<Header, Error> {
+    let demo = Demo::new(demo);
+    let header = demo.get_stream().read().map_err(ParseError::from)?;
+    Ok(header)
+}
+
+/// Find the entity and user id of a player without extracting any data
+///
+/// The player can be given as name or steam id, if no player is given the player that recorded the demo is used.
+pub fn find_player(
+    demo: &[u8],
+    player: Option<&str>,
+    lookup: &LookupOptions,
+) -> Result<(EntityId, UserId), Error> {
+    get_player(&Demo::new(demo), player.map(String::from), lookup)
+}
+
+/// Parse the per-tick data for multiple players over the entire demo in a single pass
+///
+/// The result contains one entry per selected player in the same order,
+/// or one entry per player in the order they joined when all players are selected.
+pub fn analyse_players(
+    demo: &[u8],
+    selection: &PlayerSelection,
+    lookup: &LookupOptions,
+) -> Result<(Header, Vec<PlayerTicks>), Error> {
+    let demo = Demo::new(demo);
+    let parser = DemoParser::new_all_with_analyser(
+        demo.get_stream(),
+        AmmoCountAnalyser::with_players(selection, lookup),
+    );
+    let (header, results) = parser.parse()?;
+
+    let mut players = Vec::with_capacity(results.len());
+    let mut retry = Vec::new();
+    for (index, result) in results.into_iter().enumerate() {
+        match result {
+            Ok(player) => players.push(Some(player)),
+            Err(resolver) => {
+                retry.push((index, resolver.resolve(lookup)?.steam3()));
+                players.push(None);
+            }
+        }
+    }
+
+    // only when a player can't be found in the demo, or a better match for the name joined later,
+    // do we need to parse the demo a second time with the steam ids of those players
+    if !retry.is_empty() {
+        let selection = PlayerSelection::Players(
+            retry
+                .iter()
+                .map(|(_, steam_id)| Some(steam_id.clone()))
+                .collect(),
+        );
+        let parser = DemoParser::new_all_with_analyser(
+            demo.get_stream(),
+            AmmoCountAnalyser::with_players(&selection, lookup),
+        );
+        let (_, results) = parser.parse()?;
+        for ((index, _), result) in retry.iter().zip(results) {
+            players[*index] = Some(result.map_err(|_| Error::PlayerNotFound)?);
+        }
+    }
+
+    Ok((header, players.into_iter().flatten().collect()))
+}
+
+/// Parse the per-tick data for a player over the entire demo
+///
+/// The player can be given as name or steam id, if no player is given the player that recorded the demo is used.
+pub fn analyse(
+    demo: &[u8],
+    player: Option<&str>,
+    lookup: &LookupOptions,
+) -> Result<(Header, Vec<TickData>, Errors), Error> {
+    let (header, players) = analyse_players(demo, &PlayerSelection::from(player), lookup)?;
+    let player = players.into_iter().next().ok_or(Error::PlayerNotFound)?;
+    Ok((header, player.ticks, player.errors))
+}
+
+/// Extract the data for multiple players over a range of ticks, parsing the demo only once
+///
+/// The result contains one extraction per player, in the same order as [`analyse_players`].
+pub fn extract_players(
+    demo: &[u8],
+    selection: &PlayerSelection,
+    range: RangeInclusive<u32>,
+    options: &ExtractOptions,
+) -> Result<Vec<Extraction>, Error> {
+    let (header, players) = analyse_players(demo, selection, &options.lookup)?;
+    Ok(players
+        .iter()
+        .map(|player| Extraction::new(header.clone(), player, range.clone(), options))
+        .collect())
+}
+
+/// Extract the data for a player over a range of ticks
+///
+/// The player can be given as name or steam id, if no player is given the player that recorded the demo is used.
+pub fn extract(
+    demo: &[u8],
+    player: Option<&str>,
+    range: RangeInclusive<u32>,
+    options: &ExtractOptions,
+) -> Result<Extraction, Error> {
+    extract_players(demo, &PlayerSelection::from(player), range, options)?
+        .into_iter()
+        .next()
+        .ok_or(Error::PlayerNotFound)
+}
+
+/// Extract a list of clips, parsing the demo only once for all clips and players
+///
+/// The result contains one extraction per clip, in the same order as the clips.
+pub fn extract_clips(
+    demo: &[u8],
+    clips: &[Clip],
+    options: &ExtractOptions,
+) -> Result<Vec<Extraction>, Error> {
+    let mut players = Vec::new();
+    for clip in clips {
+        if !players.contains(&clip.player) {
+            players.push(clip.player.clone());
+        }
+    }
+    let (header, ticks) = analyse_players(
+        demo,
+        &PlayerSelection::Players(players.clone()),
+        &options.lookup,
+    )?;
+
+    Ok(clips
+        .iter()
+        .map(|clip| {
+            let player = players
+                .iter()
+                .position(|player| *player == clip.player)
+                .expect("all clip players are selected");
+            Extraction::new(
+                header.clone(),
+                &ticks[player],
+                clip.start..=clip.end,
+                options,
+            )
+        })
+        .collect())
+}
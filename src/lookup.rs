@@ -0,0 +1,130 @@
+use crate::Error;
+use demostf_client::ApiClient;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fs;
+use std::path::Path;
+use steamid_ng::SteamID;
+use tf_demo_parser::demo::parser::gamestateanalyser::Team;
+use tokio::runtime::Runtime;
+
+/// How to find the player when the name matches multiple players, or none at all
+#[derive(Clone, Debug, Default)]
+pub struct LookupOptions {
+    /// Pick the n-th (1-based) of the best matching players, ordered by entity id
+    pub pick: Option<usize>,
+    /// Only consider players that were on this team at some point during the demo
+    pub team: Option<Team>,
+    /// Never search demos.tf, only the aliases are used
+    pub offline: bool,
+    pub aliases: Aliases,
+    /// Base url of the demos.tf api, defaults to `https://api.demos.tf/`
+    pub api_base_url: Option<String>,
+}
+
+/// Nicknames mapped to one or more steam ids
+#[derive(Clone, Debug, Default)]
+pub struct Aliases {
+    aliases: HashMap<String, Vec<SteamID>>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum AliasEntry {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl Aliases {
+    /// Load the aliases from a toml or json file, json is used for files with a `.json` extension
+    ///
+    /// ```toml
+    /// nick = "[U:1:12345]"
+    /// other = ["76561197960287930", "STEAM_0:0:11101"]
+    /// ```
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("failed to read aliases {}: {}", path.display(), e))?;
+        if path.extension() == Some("json".as_ref()) {
+            Self::from_json(&content)
+        } else {
+            Self::from_toml(&content)
+        }
+        .map_err(|e| format!("invalid aliases {}: {}", path.display(), e))
+    }
+
+    pub fn from_json(content: &str) -> Result<Self, String> {
+        Self::from_entries(serde_json::from_str(content).map_err(|e| e.to_string())?)
+    }
+
+    pub fn from_toml(content: &str) -> Result<Self, String> {
+        Self::from_entries(toml::from_str(content).map_err(|e| e.to_string())?)
+    }
+
+    fn from_entries(entries: HashMap<String, AliasEntry>) -> Result<Self, String> {
+        let mut aliases = HashMap::with_capacity(entries.len());
+        for (alias, entry) in entries {
+            let steam_ids = match entry {
+                AliasEntry::One(steam_id) => vec![steam_id],
+                AliasEntry::Many(steam_ids) => steam_ids,
+            };
+            let steam_ids = steam_ids
+                .iter()
+                .map(|steam_id| {
+                    SteamID::try_from(steam_id.as_str())
+                        .map_err(|_| format!("invalid steam id \"{}\" for {}", steam_id, alias))
+                })
+                .collect::<Result<_, _>>()?;
+            aliases.insert(alias.to_lowercase(), steam_ids);
+        }
+        Ok(Aliases { aliases })
+    }
+
+    /// The steam ids for a nickname, ignoring case
+    pub fn get(&self, name: &str) -> &[SteamID] {
+        self.aliases
+            .get(&name.to_lowercase())
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+}
+
+#[test]
+fn test_load_aliases() {
+    let toml = Aliases::from_toml(
+        r#"
+        Nick = "[U:1:22202]"
+        "Ärger" = "[U:1:22202]"
+        other = ["76561197960287930", "STEAM_0:0:11101"]
+    "#,
+    )
+    .unwrap();
+    let json =
+        Aliases::from_json(r#"{"nick": "76561197960287930", "other": ["[U:1:22202]"]}"#).unwrap();
+    let gaben = SteamID::from(76561197960287930);
+
+    assert_eq!(&[gaben], toml.get("nick"));
+    assert_eq!(&[gaben, gaben], toml.get("OTHER"));
+    assert_eq!(&[gaben], json.get("Nick"));
+    assert_eq!(&[gaben], json.get("other"));
+    assert_eq!(&[gaben], toml.get("ärger"));
+    assert!(toml.get("unknown").is_empty());
+    assert!(Aliases::from_json(r#"{"nick": "not a steam id"}"#).is_err());
+}
+
+/// Search demos.tf for the steam ids of users with a matching name
+pub fn search_demostf(name: &str, options: &LookupOptions) -> Result<Vec<SteamID>, Error> {
+    let client = match &options.api_base_url {
+        Some(url) => {
+            ApiClient::with_base_url(url.as_str()).map_err(|e| Error::Lookup(e.to_string()))?
+        }
+        None => ApiClient::new(),
+    };
+    let rt = Runtime::new().map_err(|e| Error::Lookup(e.to_string()))?;
+    let users = rt
+        .block_on(client.search_users(name))
+        .map_err(|e| Error::Lookup(e.to_string()))?;
+    Ok(users.into_iter().map(|user| user.steam_id).collect())
+}
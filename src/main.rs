@@ -1,652 +1,723 @@
-mod playersearch;
-mod wrapping;
-
-use crate::playersearch::get_player;
-use crate::wrapping::Wrapping;
-use cgmath::{Deg, Euler, Matrix3, Quaternion};
-use fnv::FnvHashMap;
+use ammocount::multikill::DEFAULT_MULTI_KILL_WINDOW;
+use ammocount::output::{
+    self, CsvColumn, DamageNumbers, ExportData, Metadata, OutputFormat, OutputOptions,
+};
+use ammocount::{
+    analyse_players, bookmark_clips, extract_clips, extract_players, find_bookmark_file,
+    find_highlights, list_players, load_bookmarks, load_clips, read_header, save_clips, Aliases,
+    Clip, ExtractOptions, Extraction, FrameRate, HighlightOptions, InterpolationConfig,
+    LookupOptions, PlayerSelection, DEFAULT_DAMAGE_BATCH_WINDOW,
+};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use main_error::MainError;
-use serde::Serialize;
-use splines::{Interpolation, Key, Spline};
-use std::collections::HashMap;
-use std::env::args;
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
 use std::fs;
-use std::io::Write;
-use tf_demo_parser::demo::gameevent_gen::GameEvent;
-use tf_demo_parser::demo::message::packetentities::{EntityId, PacketEntity};
-use tf_demo_parser::demo::message::Message;
-use tf_demo_parser::demo::packet::datatable::{
-    ClassId, ParseSendTable, ServerClass, ServerClassName,
-};
-use tf_demo_parser::demo::packet::message::MessagePacketMeta;
-use tf_demo_parser::demo::packet::stringtable::StringTableEntry;
-use tf_demo_parser::demo::parser::gamestateanalyser::UserId;
-use tf_demo_parser::demo::parser::MessageHandler;
-use tf_demo_parser::demo::sendprop::{SendPropIdentifier, SendPropValue};
-use tf_demo_parser::demo::vector::Vector;
-use tf_demo_parser::DemoParser;
-use tf_demo_parser::{Demo, MessageType, ParserState};
-use tracing::warn;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use tf_demo_parser::demo::parser::gamestateanalyser::Team;
+
+/// Length of the clip in ticks when no end tick is given
+const DEFAULT_CLIP_TICKS: u32 = 5000;
+
+/// Extract various information from tf2 demo files for usage in frag movies
+#[derive(Parser)]
+#[command(version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
 
-fn main() -> Result<(), MainError> {
-    let mut args = args();
-    tracing_subscriber::fmt::init();
-    let bin = args.next().unwrap();
-    let (path, user, start, end) = match (args.next(), args.next(), args.next(), args.next()) {
-        (Some(path), Some(user), Some(start), Some(end)) => (
-            path,
-            user,
-            start.parse().expect("invalid start tick"),
-            end.parse().expect("invalid end tick"),
-        ),
-        (Some(path), _, _, _) => {
-            let (user, start, end) = args_from_name(&path);
-            println!("Using {} with ticks {} to {}", user, start, end);
-            (path, user, start, end)
-        }
-        _ => {
-            println!("usage: {} <demo> [steam id] [start tick] [end tick]", bin);
-            return Ok(());
-        }
-    };
+#[derive(Subcommand)]
+enum Command {
+    /// Extract the per-frame data for a player over a range of ticks
+    Extract(ExtractArgs),
+    /// List all players in the demo
+    Players(PlayersArgs),
+    /// Find the highlights of a player, or list the damage they dealt
+    Events(EventsArgs),
+    /// Show the demo header
+    Info(DemoArgs),
+    /// Watch a directory and extract new demos once they are done recording
+    Watch(WatchArgs),
+}
 
-    let file = fs::read(&path)?;
-    let demo = Demo::new(&file);
-    let (local_player_id, local_user_id) = get_player(&demo, Some(user));
-    let parser = DemoParser::new_all_with_analyser(
-        demo.get_stream(),
-        AmmoCountAnalyser::new(local_player_id, local_user_id),
-    );
-    let (header, (state, errors)) = parser.parse()?;
-    let time_per_tick = header.duration / header.ticks as f32;
-    let ammo_path = format!("{}_ammo.txt", path);
-    let health_path = format!("{}_health.txt", path);
-    let uber_path = format!("{}_uber.txt", path);
-    let pitch_path = format!("{}_pitch.txt", path);
-    let yaw_path = format!("{}_yaw.txt", path);
-    let hit_path = format!("{}_hit.txt", path);
-    let weapon_path = format!("{}_weapon.txt", path);
-    let camera_path = format!("{}_camera.txt", path);
-    let mut ammo_out = fs::File::create(ammo_path)?;
-    let mut health_out = fs::File::create(health_path)?;
-    let mut pitch_out = fs::File::create(pitch_path)?;
-    let mut yaw_out = fs::File::create(yaw_path)?;
-    let mut hit_out = fs::File::create(hit_path)?;
-    let mut weapon_out = fs::File::create(weapon_path)?;
-    let mut camera_out = fs::File::create(camera_path)?;
-    let mut uber_out = None;
-    writeln!(&mut ammo_out, "txt = []")?;
-    writeln!(&mut health_out, "txt = []")?;
-    writeln!(&mut pitch_out, "txt = []")?;
-    writeln!(&mut yaw_out, "txt = []")?;
-    writeln!(&mut hit_out, "txt = []")?;
-    writeln!(&mut weapon_out, "txt = []")?;
-    writeln!(&mut camera_out, "txt = []")?;
-    let mut last_frame = 0;
-    let mut last_angles: Option<[f32; 2]> = None;
-
-    let mut hit_last_damage: u32 = 0;
-    let mut hit_last_tick: u32 = 0;
-    let hit_time: u32 = 33;
-
-    let pitches: Vec<_> = state
-        .iter()
-        .filter_map(|data| Some((data.tick, data.angles[0]?)))
-        .map(|(tick, pitch)| {
-            Key::new(
-                tick as f32,
-                Wrapping::<-180, 180>(pitch),
-                Interpolation::Cosine,
-            )
-        })
-        .collect();
-    let yaws: Vec<_> = state
-        .iter()
-        .filter_map(|data| Some((data.tick, data.angles[1]?)))
-        .map(|(tick, yaw)| {
-            Key::new(
-                tick as f32,
-                Wrapping::<-180, 180>(yaw),
-                Interpolation::Cosine,
-            )
-        })
-        .collect();
-    let x_s: Vec<_> = state
-        .iter()
-        .map(|data| (data.tick, data.position.x))
-        .map(|(tick, x)| Key::new(tick as f32, x, Interpolation::Linear))
-        .collect();
-    let y_s: Vec<_> = state
-        .iter()
-        .map(|data| (data.tick, data.position.y))
-        .map(|(tick, y)| Key::new(tick as f32, y, Interpolation::Linear))
-        .collect();
-    let z_s: Vec<_> = state
-        .iter()
-        .map(|data| (data.tick, data.position.z))
-        .map(|(tick, z)| Key::new(tick as f32, z, Interpolation::Linear))
-        .collect();
+#[derive(Args)]
+struct WatchArgs {
+    #[command(flatten)]
+    extract: ExtractArgs,
+    /// Seconds between checking the directory for new demos
+    #[arg(long, default_value_t = 2)]
+    interval: u64,
+}
 
-    let pitches = Spline::from_vec(pitches);
-    let yaws = Spline::from_vec(yaws);
+#[derive(Args)]
+struct DemoArgs {
+    /// The demo file
+    demo: PathBuf,
+}
 
-    let x_s = Spline::from_vec(x_s);
-    let y_s = Spline::from_vec(y_s);
-    let z_s = Spline::from_vec(z_s);
+#[derive(Args)]
+struct PlayersArgs {
+    /// The demo file
+    demo: PathBuf,
+    /// Print the players as json instead of a table
+    #[arg(long)]
+    json: bool,
+}
 
-    let mut ticks_done = 0;
+#[derive(Args)]
+struct EventsArgs {
+    /// The demo file
+    demo: PathBuf,
+    /// Name or steam id of the player, defaults to the player that recorded the demo
+    #[arg(short, long)]
+    player: Option<String>,
+    /// List every tick where the player dealt damage instead of the highlights
+    #[arg(long)]
+    hits: bool,
+    /// Only list highlights with at least this score
+    #[arg(long, default_value_t = 0)]
+    min_score: u32,
+    /// Write the highlights as toml or csv clip list that can be extracted with `extract --clips`
+    #[arg(long, conflicts_with = "hits")]
+    clips: Option<PathBuf>,
+    /// Number of ticks to include before each highlight in the clip list
+    #[arg(long, default_value_t = 500)]
+    pre_roll: u32,
+    /// Number of ticks to include after each highlight in the clip list
+    #[arg(long, default_value_t = 300)]
+    post_roll: u32,
+    /// Maximum number of seconds between two kills of a multi kill
+    #[arg(long, default_value_t = DEFAULT_MULTI_KILL_WINDOW)]
+    multi_kill_window: f32,
+    /// Maximum number of seconds between two hits of a damage burst
+    #[arg(long, default_value_t = 1.5)]
+    burst_gap: f32,
+    /// Minimum damage of a damage burst
+    #[arg(long, default_value_t = 300)]
+    burst_damage: u32,
+    /// Maximum health for a kill to count as a low health kill
+    #[arg(long, default_value_t = 40)]
+    low_health: u16,
+    #[command(flatten)]
+    lookup: LookupArgs,
+}
 
-    let start_position = Vector {
-        x: x_s.clamped_sample(start as f32).unwrap(),
-        y: y_s.clamped_sample(start as f32).unwrap(),
-        z: z_s.clamped_sample(start as f32).unwrap(),
-    };
-    let start_angles = [
-        pitches.clamped_sample(start as f32).unwrap().0,
-        yaws.clamped_sample(start as f32).unwrap().0,
-    ];
+/// Options for finding players by name when the name matches multiple players or none at all
+#[derive(Args)]
+struct LookupArgs {
+    /// Pick the n-th of the best matching players when the name matches multiple players
+    #[arg(long)]
+    pick: Option<usize>,
+    /// Only match players that were on this team at some point: red or blue
+    #[arg(long, value_parser = parse_team)]
+    team: Option<Team>,
+    /// Don't search demos.tf for player names
+    #[arg(long)]
+    offline: bool,
+    /// Toml or json file mapping nicknames to steam ids, checked before searching demos.tf
+    #[arg(long)]
+    aliases: Option<PathBuf>,
+    /// Base url of the demos.tf api
+    #[arg(long)]
+    api_url: Option<String>,
+}
 
-    for data in state
-        .into_iter()
-        .filter(|data| data.tick >= start && data.tick <= end)
-    {
-        let frame = ((data.tick - start) as f32 * time_per_tick * 120.0) as i32;
+impl LookupArgs {
+    fn options(&self) -> Result<LookupOptions, String> {
+        Ok(LookupOptions {
+            pick: self.pick,
+            team: self.team,
+            offline: self.offline,
+            aliases: match &self.aliases {
+                Some(path) => Aliases::load(path)?,
+                None => Aliases::default(),
+            },
+            api_base_url: self.api_url.clone(),
+        })
+    }
+}
 
-        if let Some(hit) = data.hit {
-            hit_last_damage = hit;
-            hit_last_tick = data.tick;
-        }
-        let hit_ratio =
-            (hit_time.saturating_sub(data.tick - hit_last_tick) as f64) / (hit_time as f64);
-        let hit_number = hit_last_damage as f64 * hit_ratio;
-
-        for frame in last_frame..frame {
-            let tick = (frame as f32) / time_per_tick / 120.0;
-            let tick = tick + start as f32;
-            let angles = [
-                pitches.clamped_sample(tick as f32).unwrap().0,
-                yaws.clamped_sample(tick as f32).unwrap().0,
-            ];
-            let position = Vector {
-                x: x_s.clamped_sample(tick as f32).unwrap(),
-                y: y_s.clamped_sample(tick as f32).unwrap(),
-                z: z_s.clamped_sample(tick as f32).unwrap(),
-            };
-            let mut delta_angles = match last_angles {
-                Some(last_angles) => [angles[0] - last_angles[0], angles[1] - last_angles[1]],
-                None => [0.0, 0.0],
-            };
+#[derive(Args)]
+struct ExtractArgs {
+    /// The demo file, named `<player>_<tick>.dem` the player and start tick default to the ones from the name,
+    /// or a directory to extract all demos in it
+    demo: PathBuf,
+    /// Number of demos to process in parallel when extracting a directory, defaults to the number of cpu cores
+    #[arg(short, long)]
+    jobs: Option<usize>,
+    /// Name or steam id of the player, defaults to the player that recorded the demo,
+    /// can be repeated to extract multiple players at once
+    #[arg(short, long)]
+    player: Vec<String>,
+    /// Extract every player in the demo
+    #[arg(long, conflicts_with = "player")]
+    all_players: bool,
+    /// What to suffix the output files with when extracting multiple players
+    #[arg(long, value_enum, default_value_t = Suffix::Name)]
+    suffix: Suffix,
+    /// Toml or csv file with a list of clips to extract, each clip is written to its own directory,
+    /// when extracting a directory a clip list named like the demo is used if it exists
+    #[arg(long, conflicts_with_all = ["player", "all_players", "start", "end"])]
+    clips: Option<PathBuf>,
+    /// `_events.txt` or `killstreaks.txt` bookmark file to create the clips from,
+    /// used automatically when it's next to a demo without a tick in its name
+    #[arg(long, conflicts_with_all = ["clips", "player", "all_players", "start", "end"])]
+    bookmarks: Option<PathBuf>,
+    /// Number of ticks to include before each bookmark
+    #[arg(long, default_value_t = 500)]
+    pre_roll: u32,
+    /// Number of ticks to include after each bookmark
+    #[arg(long, default_value_t = 300)]
+    post_roll: u32,
+    /// First tick of the clip
+    #[arg(short, long)]
+    start: Option<u32>,
+    /// Last tick of the clip, defaults to 5000 ticks after the start
+    #[arg(short, long)]
+    end: Option<u32>,
+    /// Frame rate of the output, either as decimal (`59.94`) or as fraction (`60000/1001`)
+    #[arg(long, default_value_t = FrameRate::default())]
+    fps: FrameRate,
+    /// Directory to write the output to, defaults to the directory of the demo
+    #[arg(short, long)]
+    out_dir: Option<PathBuf>,
+    /// Output formats to write: txt, jsx, blender, campath, json, ndjson, csv or csv-ticks
+    #[arg(short, long, value_delimiter = ',', default_value = "txt")]
+    format: Vec<OutputFormat>,
+    /// Smooth the campath view over the given number of frames on either side
+    #[arg(long, default_value_t = 0)]
+    campath_smoothing: usize,
+    /// Columns to include in the csv output
+    #[arg(long, value_delimiter = ',')]
+    csv_columns: Option<Vec<CsvColumn>>,
+    /// Interpolation modes per channel as `<channel>=<mode>`
+    #[arg(long, value_delimiter = ',')]
+    interpolation: Vec<String>,
+    /// Json file with the interpolation modes per channel
+    #[arg(long)]
+    interpolation_config: Option<PathBuf>,
+    /// Maximum number of seconds between two kills of a multi kill
+    #[arg(long, default_value_t = DEFAULT_MULTI_KILL_WINDOW)]
+    multi_kill_window: f32,
+    /// Damage number tracks to write in the txt output: last, per-hit, batched or life
+    #[arg(long, value_delimiter = ',')]
+    damage_numbers: Option<Vec<DamageNumbers>>,
+    /// Number of seconds after the first hit of a batch in which hits are added to the batched damage number
+    #[arg(long, default_value_t = DEFAULT_DAMAGE_BATCH_WINDOW)]
+    damage_batch_window: f32,
+    #[command(flatten)]
+    lookup: LookupArgs,
+}
 
-            if delta_angles[1] < -180.0 {
-                delta_angles[1] += 360.0;
-            }
-            if delta_angles[1] > 180.0 {
-                delta_angles[1] -= 360.0;
-            }
-            if let Some(uber) = data.uber {
-                let uber_out = uber_out.get_or_insert_with(|| {
-                    let mut uber_out = fs::File::create(&uber_path).unwrap();
-                    writeln!(&mut uber_out, "txt = []").unwrap();
-                    uber_out
-                });
-                writeln!(uber_out, "txt[{}] = \"{}\";", frame, uber)?;
-            }
-            writeln!(
-                &mut ammo_out,
-                "txt[{}] = \"{}/{}\";",
-                frame, data.ammo, data.max_ammo
-            )?;
-            writeln!(&mut health_out, "txt[{}] = \"{}\";", frame, data.health)?;
-            writeln!(&mut pitch_out, r#"txt[{}] = {};"#, frame, delta_angles[0])?;
-            writeln!(&mut yaw_out, r#"txt[{}] = {};"#, frame, delta_angles[1])?;
-            writeln!(&mut hit_out, r#"txt[{}] = {};"#, frame, hit_number as u32)?;
-            writeln!(&mut weapon_out, r#"txt[{}] = "{}";"#, frame, data.weapon)?;
-
-            #[derive(Serialize)]
-            struct CameraOut {
-                position: Vector,
-                angle: [f32; 3],
-            }
+#[derive(Clone, Copy, ValueEnum)]
+enum Suffix {
+    /// The player's name
+    Name,
+    /// The player's SteamID3 account id
+    SteamId,
+}
 
-            let yaw_matrix = <Matrix3<f32>>::from_angle_y(degrees(-(angles[1] - start_angles[1])));
-            let pitch_matrix =
-                <Matrix3<f32>>::from_angle_x(degrees(-(angles[0] - start_angles[0])));
-            let quat = <Quaternion<f32>>::from(yaw_matrix * pitch_matrix);
-            let euler = Euler::from(quat);
-            writeln!(
-                &mut camera_out,
-                r#"txt[{}] = {};"#,
-                frame,
-                serde_json::to_string(&CameraOut {
-                    position: position - start_position,
-                    angle: [
-                        Deg::from(euler.x).0,
-                        Deg::from(euler.y).0,
-                        Deg::from(euler.z).0
-                    ]
-                })
-                .unwrap()
-            )?;
-            ticks_done += 1;
-            last_angles = Some(angles);
+impl Suffix {
+    fn for_player(self, extraction: &Extraction) -> String {
+        match (self, &extraction.steam_id) {
+            (Suffix::SteamId, Some(steam_id)) => {
+                // `[U:1:12345]` -> `12345`
+                steam_id
+                    .trim_end_matches(']')
+                    .rsplit(':')
+                    .next()
+                    .unwrap_or_default()
+                    .to_string()
+            }
+            _ => extraction
+                .player
+                .chars()
+                .map(|c| if c.is_alphanumeric() { c } else { '_' })
+                .collect(),
         }
-        last_frame = frame;
     }
-    println!("{} frames processed", ticks_done);
-
-    errors.show();
-    Ok(())
 }
 
-pub struct TickData {
-    tick: u32,
-    ammo: u16,
-    max_ammo: u16,
-    health: u16,
-    uber: Option<u8>,
-    angles: [Option<f32>; 2],
-    hit: Option<u32>,
-    weapon: String,
-    position: Vector,
+fn main() -> Result<(), MainError> {
+    // logs go to stderr, stdout is reserved for the output of the commands
+    tracing_subscriber::fmt().with_writer(io::stderr).init();
+    let cli = Cli::parse();
+
+    match cli.command {
+        Command::Extract(args) => run_extract(args),
+        Command::Players(args) => run_players(args),
+        Command::Events(args) => run_events(args),
+        Command::Info(args) => run_info(args),
+        Command::Watch(args) => run_watch(args),
+    }
 }
 
-#[derive(Default)]
-pub struct AmmoCountAnalyser {
-    tick: u32,
-    output: Vec<TickData>,
-    max_clip: FnvHashMap<EntityId, u16>,
-    clip: FnvHashMap<EntityId, u16>,
-    current_health: u16,
-    class_names: Vec<ServerClassName>,
-    local_player_id: EntityId,
-    local_user_id: UserId,
-    entity_classes: FnvHashMap<EntityId, ClassId>,
-    outer_map: FnvHashMap<i64, EntityId>,
-    model_names: Vec<String>,
-    active_weapon: i64,
-    last_tick: u32,
-    ammo: [u16; 2],
-    max_ammo: [u16; 2],
-    uber: u8,
-    has_uber: bool,
-    angles: [f32; 2],
-    errors: Errors,
-    hit: Option<u32>,
-    pov: EntityId,
-    model_indexes: HashMap<EntityId, u32>,
-    tick_angles: [Option<f32>; 2],
-    angle_delta_tick: [u32; 2],
-    loadout: [i64; 2],
-    position: Vector,
+fn run_extract(args: ExtractArgs) -> Result<(), MainError> {
+    let options = extract_options(&args)?;
+    if args.demo.is_dir() {
+        return run_batch(&args, &options);
+    }
+    extract_demo(&args, &options, &args.demo, args.clips.as_deref())?;
+    Ok(())
 }
 
-impl MessageHandler for AmmoCountAnalyser {
-    type Output = (Vec<TickData>, Errors);
-
-    fn does_handle(_message_type: MessageType) -> bool {
-        true
+fn extract_options(args: &ExtractArgs) -> Result<ExtractOptions, MainError> {
+    let mut interpolation = match &args.interpolation_config {
+        Some(path) => InterpolationConfig::load(path)?,
+        None => InterpolationConfig::default(),
+    };
+    for spec in &args.interpolation {
+        interpolation.apply(spec)?;
     }
-
-    fn handle_message(&mut self, message: &Message, tick: u32, state: &ParserState) {
-        match message {
-            Message::ServerInfo(info) => {
-                self.pov = (info.player_slot as u32 + 1).into();
-            }
-            Message::PacketEntities(entities) => {
-                for entity in &entities.entities {
-                    self.handle_entity(tick, entity, state)
-                }
-            }
-            Message::GameEvent(event_msg) => {
-                self.handle_event(&event_msg.event);
-            }
-            _ => {}
+    // checked up front so an unavailable column doesn't fail only after the demo is parsed
+    if let Some(columns) = &args.csv_columns {
+        if args.format.contains(&OutputFormat::Csv) {
+            CsvColumn::check_frame_columns(columns)?;
+        }
+        if args.format.contains(&OutputFormat::CsvTicks) {
+            CsvColumn::check_tick_columns(columns)?;
         }
     }
+    Ok(ExtractOptions {
+        frame_rate: args.fps,
+        interpolation,
+        lookup: args.lookup.options()?,
+        multi_kill_window: args.multi_kill_window,
+        damage_batch_window: args.damage_batch_window,
+    })
+}
 
-    fn handle_string_entry(
-        &mut self,
-        table: &str,
-        index: usize,
-        entry: &StringTableEntry,
-        _state: &ParserState,
-    ) {
-        match table {
-            "modelprecache" => {
-                let model = entry.text.as_deref().unwrap_or_default();
-                let file_name = model.rsplit_once('/').unwrap_or_default().1;
-                let name = file_name.split_once('.').unwrap_or_default().0;
-                self.model_names.insert(index, name.to_string())
+/// Extract a single demo, using the clip list if given
+fn extract_demo(
+    args: &ExtractArgs,
+    options: &ExtractOptions,
+    demo: &Path,
+    clips: Option<&Path>,
+) -> Result<(), Box<dyn Error>> {
+    let file_name = demo
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let out_dir = match &args.out_dir {
+        Some(out_dir) => out_dir.clone(),
+        None => demo.parent().map(Path::to_path_buf).unwrap_or_default(),
+    };
+
+    // every extraction with the base path for its output files
+    let mut outputs = Vec::new();
+    let from_name = args_from_name(demo);
+    let clips = if let Some(clips) = clips {
+        Some(load_clips(clips)?)
+    } else if let Some(bookmarks) = &args.bookmarks {
+        Some(clips_from_bookmarks(args, demo, bookmarks)?)
+    } else if from_name.is_none()
+        && args.start.is_none()
+        && args.player.is_empty()
+        && !args.all_players
+    {
+        match find_bookmark_file(demo) {
+            Some(bookmarks) => {
+                let clips = clips_from_bookmarks(args, demo, &bookmarks)?;
+                println!(
+                    "Using {} clips from bookmarks in {}",
+                    clips.len(),
+                    bookmarks.display()
+                );
+                Some(clips)
             }
-            _ => {}
+            None => None,
         }
-    }
+    } else {
+        None
+    };
 
-    fn handle_data_tables(
-        &mut self,
-        _parse_tables: &[ParseSendTable],
-        server_classes: &[ServerClass],
-        _state: &ParserState,
-    ) {
-        self.class_names = server_classes
-            .iter()
-            .map(|class| &class.name)
-            .cloned()
-            .collect();
-    }
+    if let Some(clips) = clips {
+        let file = fs::read(demo)?;
+        let extractions = extract_clips(&file, &clips, options)?;
+
+        let mut names = Vec::with_capacity(clips.len());
+        for (index, (clip, extraction)) in clips.iter().zip(extractions).enumerate() {
+            let mut name = clip.name();
+            if names.contains(&name) {
+                name = format!("{}_{}", name, index + 1);
+            }
+            let clip_dir = out_dir.join(&name);
+            fs::create_dir_all(&clip_dir)?;
+            names.push(name);
+            outputs.push((extraction, clip_dir.join(&file_name)));
+        }
+    } else {
+        let selection = if args.all_players {
+            PlayerSelection::All
+        } else if !args.player.is_empty() {
+            PlayerSelection::Players(args.player.iter().cloned().map(Some).collect())
+        } else {
+            PlayerSelection::from(from_name.as_ref().map(|(name, _)| name.as_str()))
+        };
+        let start = args
+            .start
+            .or_else(|| from_name.as_ref().map(|(_, tick)| *tick))
+            .ok_or("no --start given and no tick found in the demo file name")?;
+        let end = args.end.unwrap_or(start + DEFAULT_CLIP_TICKS);
+        if end < start {
+            return Err(format!("end tick {} is before start tick {}", end, start).into());
+        }
+        if args.start.is_none() {
+            println!("Using ticks {} to {}", start, end);
+        }
 
-    fn handle_packet_meta(&mut self, tick: u32, meta: &MessagePacketMeta, _state: &ParserState) {
-        if self.is_pov() {
-            self.angles = [meta.view_angles[0].angles.x, meta.view_angles[0].angles.y];
-            self.position = meta.view_angles[0].origin;
-            self.tick_angles = [Some(self.angles[0]), Some(self.angles[1])];
+        let file = fs::read(demo)?;
+        let extractions = extract_players(&file, &selection, start..=end, options)?;
+        fs::create_dir_all(&out_dir)?;
+        // the outputs are only suffixed when there can be multiple players
+        let suffix = match &selection {
+            PlayerSelection::All => Some(args.suffix),
+            PlayerSelection::Players(players) if players.len() > 1 => Some(args.suffix),
+            _ => None,
+        };
+        for extraction in extractions {
+            let base_path = match suffix {
+                Some(suffix) => {
+                    out_dir.join(format!("{}_{}", file_name, suffix.for_player(&extraction)))
+                }
+                None => out_dir.join(&file_name),
+            };
+            outputs.push((extraction, base_path));
         }
-        self.tick = tick;
     }
 
-    fn into_output(self, _state: &ParserState) -> Self::Output {
-        (self.output, self.errors)
+    let output_options = OutputOptions {
+        campath_smoothing: args.campath_smoothing,
+        csv_columns: args.csv_columns.clone(),
+        damage_numbers: args.damage_numbers.clone(),
+    };
+    for (extraction, base_path) in &outputs {
+        let header = &extraction.header;
+        let metadata = Metadata {
+            demo: file_name.clone(),
+            map: header.map.clone(),
+            server: header.server.clone(),
+            nick: header.nick.clone(),
+            duration: header.duration,
+            ticks: header.ticks,
+            player: extraction.player.clone(),
+            start_tick: *extraction.range.start(),
+            end_tick: *extraction.range.end(),
+            fps: args.fps.fps(),
+        };
+        let data = ExportData {
+            frames: &extraction.frames,
+            kill_feed: &extraction.kill_feed,
+            multi_kills: &extraction.multi_kills,
+            ticks: &extraction.ticks,
+            timing: &extraction.timing,
+            metadata: &metadata,
+        };
+
+        for format in &args.format {
+            output::write(
+                *format,
+                &base_path.to_string_lossy(),
+                &data,
+                &output_options,
+            )?;
+        }
+        println!(
+            "{}: {} frames processed at {} fps",
+            base_path.display(),
+            extraction.frames.len(),
+            args.fps
+        );
+        extraction.errors.show();
     }
+    Ok(())
 }
 
-const CLIP_PROP: SendPropIdentifier = SendPropIdentifier::new("DT_LocalWeaponData", "m_iClip1");
-const OUTER_CONTAINER_PROP: SendPropIdentifier =
-    SendPropIdentifier::new("DT_AttributeContainer", "m_hOuter");
-const ACTIVE_WEAPON_PROP: SendPropIdentifier =
-    SendPropIdentifier::new("DT_BaseCombatCharacter", "m_hActiveWeapon");
-const HEALTH_PROP: SendPropIdentifier = SendPropIdentifier::new("DT_BasePlayer", "m_iHealth");
-#[allow(dead_code)]
-const UBER_CHARGE_PROP: SendPropIdentifier =
-    SendPropIdentifier::new("DT_TFWeaponMedigunDataNonLocal", "m_flChargeLevel");
-#[allow(dead_code)]
-const UBER_CHARGE_PROP_LOCAL: SendPropIdentifier =
-    SendPropIdentifier::new("DT_LocalTFWeaponMedigunData", "m_flChargeLevel");
-#[allow(dead_code)]
-const DAMAGE_PROP_LOCAL: SendPropIdentifier =
-    SendPropIdentifier::new("DT_TFPlayerScoringDataExclusive", "m_iDamageDone");
-#[allow(dead_code)]
-const EYE_ANGLES_X: SendPropIdentifier =
-    SendPropIdentifier::new("DT_TFNonLocalPlayerExclusive", "m_angEyeAngles[0]");
-#[allow(dead_code)]
-const EYE_ANGLES_Y: SendPropIdentifier =
-    SendPropIdentifier::new("DT_TFNonLocalPlayerExclusive", "m_angEyeAngles[1]");
-#[allow(dead_code)]
-const EYE_POS_X: SendPropIdentifier =
-    SendPropIdentifier::new("DT_TFNonLocalPlayerExclusive", "m_vecOrigin[0]");
-#[allow(dead_code)]
-const EYE_POS_Y: SendPropIdentifier =
-    SendPropIdentifier::new("DT_TFNonLocalPlayerExclusive", "m_vecOrigin[1]");
-#[allow(dead_code)]
-const EYE_POS_Z: SendPropIdentifier =
-    SendPropIdentifier::new("DT_TFNonLocalPlayerExclusive", "m_vecOrigin[2]");
-
-#[allow(dead_code)]
-const WEAPON1_ID_PROP: SendPropIdentifier = SendPropIdentifier::new("m_hMyWeapons", "000");
-#[allow(dead_code)]
-const WEAPON2_ID_PROP: SendPropIdentifier = SendPropIdentifier::new("m_hMyWeapons", "001");
-#[allow(dead_code)]
-const WEAPON3_ID_PROP: SendPropIdentifier = SendPropIdentifier::new("m_hMyWeapons", "002");
-
-#[allow(dead_code)]
-const AMMO1_PROP: SendPropIdentifier = SendPropIdentifier::new("m_iAmmo", "001");
-#[allow(dead_code)]
-const AMMO2_PROP: SendPropIdentifier = SendPropIdentifier::new("m_iAmmo", "002");
-
-#[allow(dead_code)]
-const MODEL_INDEX: SendPropIdentifier =
-    SendPropIdentifier::new("DT_BaseCombatWeapon", "m_iWorldModelIndex");
-
-const OUTER_NULL: i64 = 0x1FFFFF;
-
-impl AmmoCountAnalyser {
-    pub fn new(local_player_id: EntityId, local_user_id: UserId) -> Self {
-        AmmoCountAnalyser {
-            local_player_id,
-            local_user_id,
-            ..Default::default()
+/// Extract every demo in a directory in parallel, failing demos don't stop the other demos from being processed
+fn run_batch(args: &ExtractArgs, options: &ExtractOptions) -> Result<(), MainError> {
+    let demos = list_demos(&args.demo)?;
+    let jobs = args
+        .jobs
+        .or_else(|| thread::available_parallelism().ok().map(usize::from))
+        .unwrap_or(1)
+        .clamp(1, demos.len().max(1));
+    println!("Processing {} demos with {} jobs", demos.len(), jobs);
+
+    let next = AtomicUsize::new(0);
+    let done = AtomicUsize::new(0);
+    let failed = Mutex::new(Vec::new());
+    thread::scope(|scope| {
+        for _ in 0..jobs {
+            scope.spawn(|| {
+                while let Some(demo) = demos.get(next.fetch_add(1, Ordering::SeqCst)) {
+                    let clips = args.clips.clone().or_else(|| find_clip_list(demo));
+                    // the error isn't `Send`, only its message is passed on
+                    let result = extract_demo(args, options, demo, clips.as_deref())
+                        .map_err(|e| e.to_string());
+                    let done = done.fetch_add(1, Ordering::SeqCst) + 1;
+                    match result {
+                        Ok(()) => println!("[{}/{}] {}", done, demos.len(), demo.display()),
+                        Err(e) => {
+                            eprintln!("[{}/{}] {}: {}", done, demos.len(), demo.display(), e);
+                            failed.lock().unwrap().push((demo, e));
+                        }
+                    }
+                }
+            });
         }
-    }
+    });
 
-    fn is_pov(&self) -> bool {
-        self.pov == self.local_player_id
+    let failed = failed.into_inner().unwrap();
+    println!(
+        "Processed {} demos, {} failed",
+        demos.len() - failed.len(),
+        failed.len()
+    );
+    if failed.is_empty() {
+        return Ok(());
+    }
+    for (demo, e) in &failed {
+        eprintln!("{}: {}", demo.display(), e);
     }
+    Err(format!("{} of {} demos failed", failed.len(), demos.len()).into())
+}
 
-    #[allow(dead_code)]
-    fn server_class(&self, id: ClassId) -> &str {
-        self.class_names[u16::from(id) as usize].as_str()
+/// Extract every demo added to the directory once it's done recording
+///
+/// A demo is done when its size hasn't changed since the last check and the header has been written,
+/// which tf2 only does when the recording stops.
+fn run_watch(args: WatchArgs) -> Result<(), MainError> {
+    let interval = Duration::from_secs(args.interval.max(1));
+    let args = args.extract;
+    let options = extract_options(&args)?;
+    let dir = &args.demo;
+    if !dir.is_dir() {
+        return Err(format!("{} is not a directory", dir.display()).into());
     }
 
-    fn handle_event(&mut self, event: &GameEvent) {
-        match event {
-            GameEvent::PlayerSpawn(spawn) => {
-                if UserId::from(spawn.user_id) == self.local_user_id {
-                    self.clip = self.max_clip.clone();
-                }
+    // demos that were already there are left alone
+    let mut done: HashSet<PathBuf> = list_demos(dir)?.into_iter().collect();
+    let mut sizes: HashMap<PathBuf, u64> = HashMap::new();
+    println!("Watching {} for new demos", dir.display());
+
+    loop {
+        thread::sleep(interval);
+        let demos = match list_demos(dir) {
+            Ok(demos) => demos,
+            // the directory can be unavailable for a moment, like a network share reconnecting
+            Err(e) => {
+                eprintln!("{}: {}", dir.display(), e);
+                continue;
             }
-            GameEvent::PlayerHurt(event) => {
-                if UserId::from(event.attacker) == self.local_user_id {
-                    self.hit = Some(event.damage_amount as u32);
-                }
+        };
+        for demo in demos {
+            if done.contains(&demo) {
+                continue;
+            }
+            let size = match fs::metadata(&demo) {
+                Ok(metadata) => metadata.len(),
+                // removed again before we got to it
+                Err(_) => continue,
+            };
+            if sizes.insert(demo.clone(), size) != Some(size) || !is_finished(&demo) {
+                continue;
             }
-            _ => {}
-        }
-    }
 
-    fn handle_entity(&mut self, tick: u32, entity: &PacketEntity, state: &ParserState) {
-        for prop in entity.props(state) {
-            match prop.value {
-                SendPropValue::Integer(value) if value != OUTER_NULL => {
-                    if let Some((table_name, prop_name)) = prop.identifier.names() {
-                        if table_name == "m_iChargeLevel" {
-                            let entity_id: u32 = prop_name.parse().unwrap();
-                            if EntityId::from(entity_id) == self.local_player_id {
-                                if value > 0 {
-                                    self.has_uber = true;
-                                }
-                                self.uber = value as u8;
-                            }
-                        }
-                    }
-                    match prop.identifier {
-                        ACTIVE_WEAPON_PROP if entity.entity_index == self.local_player_id => {
-                            self.active_weapon = value;
-                        }
-                        AMMO1_PROP if entity.entity_index == self.local_player_id => {
-                            self.ammo[0] = value as u16;
-                            self.max_ammo[0] = self.max_ammo[0].max(value as u16);
-                        }
-                        AMMO2_PROP if entity.entity_index == self.local_player_id => {
-                            self.ammo[1] = value as u16;
-                            self.max_ammo[1] = self.max_ammo[1].max(value as u16);
-                        }
-                        HEALTH_PROP if entity.entity_index == self.local_player_id => {
-                            self.current_health = value as u16;
-                        }
-                        OUTER_CONTAINER_PROP => {
-                            self.outer_map.insert(value, entity.entity_index);
-                        }
-                        CLIP_PROP => {
-                            match self.entity_classes.get(&entity.entity_index) {
-                                Some(class) if *class != entity.server_class => {
-                                    self.max_clip.insert(entity.entity_index, value as u16);
-                                }
-                                _ => {
-                                    let clip_max =
-                                        self.max_clip.entry(entity.entity_index).or_default();
-                                    *clip_max = (*clip_max).max(value as u16);
-                                }
-                            }
-                            self.clip.insert(entity.entity_index, value as u16);
-                        }
-                        MODEL_INDEX => {
-                            self.model_indexes.insert(entity.entity_index, value as u32);
-                        }
-                        WEAPON1_ID_PROP if entity.entity_index == self.local_player_id => {
-                            if value != self.loadout[0] {
-                                self.max_ammo[0] = 0;
-                                self.ammo[0] = 0;
-                                self.loadout[0] = value;
-                            }
-                        }
-                        WEAPON2_ID_PROP if entity.entity_index == self.local_player_id => {
-                            if value != self.loadout[1] {
-                                self.max_ammo[1] = 0;
-                                self.ammo[1] = 0;
-                                self.loadout[1] = value;
-                            }
-                        }
-                        _ => {}
-                    }
-                }
-                SendPropValue::Float(value)
-                    if !self.is_pov() && entity.entity_index == self.local_player_id =>
-                {
-                    match prop.identifier {
-                        EYE_ANGLES_X => {
-                            self.angles[0] = value;
-                            self.tick_angles[0] = Some(value);
-                            self.angle_delta_tick[0] = tick;
-                        }
-                        EYE_ANGLES_Y => {
-                            self.angles[1] = value;
-                            self.tick_angles[1] = Some(value);
-                            self.angle_delta_tick[1] = tick;
-                        }
-                        EYE_POS_X => {
-                            self.position.x = value;
-                        }
-                        EYE_POS_Y => {
-                            self.position.y = value;
-                        }
-                        EYE_POS_Z => {
-                            self.position.z = value;
-                        }
-                        _ => {}
-                    }
-                }
-                _ => {}
+            sizes.remove(&demo);
+            let clips = args.clips.clone().or_else(|| find_clip_list(&demo));
+            match extract_demo(&args, &options, &demo, clips.as_deref()) {
+                Ok(()) => println!("{}: done", demo.display()),
+                Err(e) => eprintln!("{}: {}", demo.display(), e),
             }
+            done.insert(demo);
         }
+    }
+}
 
-        self.entity_classes
-            .insert(entity.entity_index, entity.server_class);
+/// Check if tf2 is done recording the demo, the tick count in the header is only set once the recording stops
+fn is_finished(demo: &Path) -> bool {
+    fs::read(demo)
+        .ok()
+        .and_then(|file| read_header(&file).ok())
+        .filter(|header| header.ticks > 0)
+        .is_some()
+}
 
-        if self.tick > self.last_tick {
-            let active_slot = if self.active_weapon == self.loadout[0] {
-                0
-            } else {
-                1
-            };
-            if let Some(active_weapon) = self.outer_map.get(&self.active_weapon) {
-                if self.clip.contains_key(active_weapon) {
-                    let mut ammo = if self.max_clip[active_weapon] > 0 {
-                        self.clip[active_weapon].saturating_sub(1)
-                    } else {
-                        self.ammo[active_slot]
-                    };
-                    let mut max_ammo = if self.max_clip[active_weapon] > 0 {
-                        self.max_clip[active_weapon].saturating_sub(1)
-                    } else {
-                        self.max_ammo[active_slot]
-                    };
-
-                    let model_index = self
-                        .model_indexes
-                        .get(active_weapon)
-                        .copied()
-                        .unwrap_or_default();
-
-                    let weapon = self
-                        .model_names
-                        .get(model_index as usize)
-                        .cloned()
-                        .unwrap_or_default();
-
-                    if let Some(max_overwrite) = max_clip_overwrite(&weapon) {
-                        max_ammo = max_overwrite;
-                    }
-                    ammo = ammo.min(max_ammo);
-
-                    self.output.push(TickData {
-                        tick: self.tick,
-                        ammo,
-                        max_ammo,
-                        health: self.current_health,
-                        uber: self.has_uber.then(|| self.uber),
-                        angles: self.tick_angles,
-                        hit: self.hit,
-                        weapon,
-                        position: self.position,
-                    });
-
-                    self.hit = None;
-                    self.tick_angles = [None, None];
-                } else {
-                    self.errors.clip_not_found += 1;
-                    warn!(
-                        tick = self.tick,
-                        weapon_handle = self.active_weapon,
-                        weapon_id = display(active_weapon),
-                        "can't find clip"
-                    );
-                }
-            } else if self.active_weapon > 0 {
-                self.errors.weapon_not_found += 1;
-                warn!(
-                    tick = self.tick,
-                    weapon_handle = self.active_weapon,
-                    "can't find weapon"
-                );
-            } else {
-                self.errors.no_weapon_set += 1;
-            }
-            self.last_tick = self.tick;
+/// All `.dem` files in a directory, sorted by name
+fn list_demos(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut demos = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension() == Some("dem".as_ref()) {
+            demos.push(path);
         }
     }
+    demos.sort();
+    Ok(demos)
 }
 
-#[derive(Default)]
-pub struct Errors {
-    no_weapon_set: u32,
-    weapon_not_found: u32,
-    clip_not_found: u32,
+/// Find a clip list next to the demo, named like the demo with a `.toml` or `.csv` extension
+fn find_clip_list(demo: &Path) -> Option<PathBuf> {
+    ["toml", "csv"]
+        .iter()
+        .map(|extension| demo.with_extension(extension))
+        .find(|path| path.is_file())
 }
 
-impl Errors {
-    fn show(&self) {
-        if self.no_weapon_set > 0 {
-            eprint!("No weapon set {} times", self.no_weapon_set);
-        }
-        if self.weapon_not_found > 0 {
-            eprint!("Weapon not found {} times", self.weapon_not_found);
+fn run_players(args: PlayersArgs) -> Result<(), MainError> {
+    let file = fs::read(&args.demo)?;
+    let players = list_players(&file)?;
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&players)?);
+        return Ok(());
+    }
+
+    println!(
+        "{:<17}  {:<22}  {:<9}  {:<24}  {:>6}  {:>4}  {:>7}  {:>7}  name",
+        "steam id", "steam id3", "team", "classes", "entity", "user", "join", "leave"
+    );
+    for player in players {
+        println!(
+            "{:<17}  {:<22}  {:<9}  {:<24}  {:>6}  {:>4}  {:>7}  {:>7}  {}",
+            player.steam_id.as_deref().unwrap_or("BOT"),
+            player.steam_id3.as_deref().unwrap_or("BOT"),
+            player.team_name(),
+            player.class_names().join(","),
+            player.entity_id.to_string(),
+            u8::from(player.user_id),
+            player.join_tick,
+            player
+                .leave_tick
+                .map(|tick| tick.to_string())
+                .unwrap_or_default(),
+            player.name
+        );
+    }
+    Ok(())
+}
+
+fn run_events(args: EventsArgs) -> Result<(), MainError> {
+    let file = fs::read(&args.demo)?;
+    let lookup = args.lookup.options()?;
+    let (header, players) = analyse_players(
+        &file,
+        &PlayerSelection::from(args.player.as_deref()),
+        &lookup,
+    )?;
+    let player = players
+        .into_iter()
+        .next()
+        .ok_or(ammocount::Error::PlayerNotFound)?;
+
+    if args.hits {
+        println!("{:>7} {:>6} {:>6}  weapon", "tick", "damage", "health");
+        for tick in player.ticks.iter().filter(|tick| tick.hit.is_some()) {
+            println!(
+                "{:>7} {:>6} {:>6}  {}",
+                tick.tick,
+                tick.hit.unwrap_or_default(),
+                tick.health,
+                tick.weapon
+            );
         }
-        if self.clip_not_found > 0 {
-            eprint!("Clip not found {} times", self.clip_not_found);
+        player.errors.show();
+        return Ok(());
+    }
+
+    let options = HighlightOptions {
+        multi_kill_window: args.multi_kill_window,
+        burst_gap: args.burst_gap,
+        burst_damage: args.burst_damage,
+        low_health: args.low_health,
+    };
+    let time_per_tick = header.duration / header.ticks as f32;
+    let highlights: Vec<_> = find_highlights(&player.ticks, &options, time_per_tick)
+        .into_iter()
+        .filter(|highlight| highlight.score >= args.min_score)
+        .collect();
+
+    if let Some(path) = &args.clips {
+        // the steam id makes sure the clips are extracted for the same player
+        let clip_player = player.steam_id.clone().or_else(|| args.player.clone());
+        let clips: Vec<_> = highlights
+            .iter()
+            .map(|highlight| highlight.clip(clip_player.clone(), args.pre_roll, args.post_roll))
+            .collect();
+        save_clips(path, &clips)?;
+        println!("Wrote {} clips to {}", clips.len(), path.display());
+    } else {
+        println!("{:>7} {:>7} {:>5}  highlight", "start", "end", "score");
+        for highlight in &highlights {
+            println!(
+                "{:>7} {:>7} {:>5}  {}",
+                highlight.start_tick,
+                highlight.end_tick,
+                highlight.score,
+                highlight.name()
+            );
         }
     }
+
+    player.errors.show();
+    Ok(())
 }
 
-fn args_from_name(name: &str) -> (String, u32, u32) {
-    let name = name.rsplit_once('/').unwrap_or(("", name)).1;
-    let name = name.split_once('.').unwrap_or((name, "")).0;
-    let mut parts = name.split('_');
-    let name = parts.next().expect("unexpected name format").to_lowercase();
-    let tick = parts
-        .next()
-        .expect("unexpected name format")
-        .to_lowercase()
-        .parse()
-        .expect("unexpected name format");
-    (name, tick, tick + 5000)
+fn run_info(args: DemoArgs) -> Result<(), MainError> {
+    let file = fs::read(&args.demo)?;
+    let header = read_header(&file)?;
+
+    println!("map:      {}", header.map);
+    println!("server:   {}", header.server);
+    println!("nick:     {}", header.nick);
+    println!("duration: {:.1}s", header.duration);
+    println!("ticks:    {}", header.ticks);
+    println!("frames:   {}", header.frames);
+    if header.ticks > 0 {
+        println!(
+            "tickrate: {:.1}",
+            header.ticks as f32 / header.duration.max(f32::EPSILON)
+        );
+    }
+    Ok(())
+}
+
+fn parse_team(team: &str) -> Result<Team, String> {
+    match team.to_ascii_lowercase().as_str() {
+        "red" => Ok(Team::Red),
+        "blu" | "blue" => Ok(Team::Blue),
+        _ => Err(format!("unknown team \"{}\", expected red or blue", team)),
+    }
 }
 
-fn max_clip_overwrite(weapon: &str) -> Option<u16> {
-    match weapon {
-        "c_pep_pistol" => Some(9),
-        _ => None,
+/// Create clips from the entries in a bookmark file that belong to the demo
+fn clips_from_bookmarks(
+    args: &ExtractArgs,
+    demo: &Path,
+    bookmarks: &Path,
+) -> Result<Vec<Clip>, String> {
+    let demo_name = demo
+        .file_stem()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let bookmarks = load_bookmarks(bookmarks)?;
+    let clips = bookmark_clips(&bookmarks, &demo_name, args.pre_roll, args.post_roll);
+    if clips.is_empty() {
+        return Err(format!("no bookmarks found for {}", demo_name));
     }
+    Ok(clips)
 }
 
-pub const fn degrees(v: f32) -> Deg<f32> {
-    Deg(v)
+/// Get the player and start tick from a demo named like `<player>_<tick>.dem`
+fn args_from_name(path: &Path) -> Option<(String, u32)> {
+    let name = path.file_stem()?.to_str()?;
+    let (player, tick) = name.split_once('_')?;
+    let tick = tick.split('_').next()?.parse().ok()?;
+    Some((player.to_lowercase(), tick))
 }
@@ -0,0 +1,158 @@
+use crate::timing::Timing;
+use crate::TickData;
+use serde::Serialize;
+
+/// Default maximum time between two kills of a multi kill, in seconds
+pub const DEFAULT_MULTI_KILL_WINDOW: f32 = 3.0;
+
+/// Keeps track of the kills in quick succession
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MultiKillTracker {
+    /// Maximum number of ticks between two kills
+    window: u32,
+    start_tick: u32,
+    last_tick: u32,
+    kills: u16,
+}
+
+impl MultiKillTracker {
+    pub fn new(window: u32) -> Self {
+        MultiKillTracker {
+            window,
+            ..Default::default()
+        }
+    }
+
+    /// Add the kills made at a tick, returns the finished multi kill if the new kills are too late to continue it
+    pub fn push(&mut self, tick: u32, kills: u16) -> Option<MultiKill> {
+        let finished = self.finish_before(tick);
+        if kills > 0 {
+            if self.kills == 0 {
+                self.start_tick = tick;
+            }
+            self.kills += kills;
+            self.last_tick = tick;
+        }
+        finished
+    }
+
+    /// The multi kill if it can't continue at the tick anymore
+    pub fn finish_before(&mut self, tick: u32) -> Option<MultiKill> {
+        if self.kills > 0 && tick > self.last_tick + self.window {
+            let multi_kill = self.current();
+            self.kills = 0;
+            multi_kill
+        } else {
+            None
+        }
+    }
+
+    /// Number of kills in the multi kill that's going on at the tick, `0` if there is none
+    pub fn active(&self, tick: f32) -> u16 {
+        if self.kills >= 2 && tick <= (self.last_tick + self.window) as f32 {
+            self.kills
+        } else {
+            0
+        }
+    }
+
+    fn current(&self) -> Option<MultiKill> {
+        (self.kills >= 2).then_some(MultiKill {
+            start_tick: self.start_tick,
+            end_tick: self.last_tick,
+            kills: self.kills,
+        })
+    }
+}
+
+/// Two or more kills with at most the multi kill window between them
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct MultiKill {
+    /// Tick of the first kill
+    pub start_tick: u32,
+    /// Tick of the last kill
+    pub end_tick: u32,
+    pub kills: u16,
+}
+
+impl MultiKill {
+    pub fn name(&self) -> &'static str {
+        match self.kills {
+            2 => "double kill",
+            3 => "triple kill",
+            4 => "quad kill",
+            _ => "multi kill",
+        }
+    }
+}
+
+/// A multi kill with the frames of the first and last kill
+#[derive(Clone, Debug, Serialize)]
+pub struct MultiKillMarker {
+    #[serde(flatten)]
+    pub multi_kill: MultiKill,
+    pub name: &'static str,
+    pub start_frame: i32,
+    pub end_frame: i32,
+}
+
+/// Find all multi kills in the per-tick data, `window` is the maximum number of ticks between two kills
+pub fn find_multi_kills(ticks: &[TickData], window: u32) -> Vec<MultiKill> {
+    let mut tracker = MultiKillTracker::new(window);
+    let mut multi_kills: Vec<MultiKill> = ticks
+        .iter()
+        .filter_map(|data| tracker.push(data.tick, data.kills))
+        .collect();
+    multi_kills.extend(tracker.current());
+    multi_kills
+}
+
+/// Markers for the multi kills that end within the `[start, end]` tick range
+pub fn multi_kill_markers(
+    ticks: &[TickData],
+    window: u32,
+    timing: &Timing,
+    start: u32,
+    end: u32,
+) -> Vec<MultiKillMarker> {
+    find_multi_kills(ticks, window)
+        .into_iter()
+        .filter(|multi_kill| multi_kill.end_tick >= start && multi_kill.end_tick <= end)
+        .map(|multi_kill| MultiKillMarker {
+            multi_kill,
+            name: multi_kill.name(),
+            start_frame: timing.frame_for_tick(multi_kill.start_tick),
+            end_frame: timing.frame_for_tick(multi_kill.end_tick),
+        })
+        .collect()
+}
+
+#[test]
+fn test_multi_kill_tracker() {
+    let mut tracker = MultiKillTracker::new(200);
+    let kills = [(100, 1), (250, 1), (400, 2), (700, 1), (1000, 1), (1100, 1)];
+    let mut found = Vec::new();
+    for (tick, count) in kills.iter().copied() {
+        found.extend(tracker.push(tick, count));
+    }
+    assert_eq!(2, tracker.active(1200.0));
+    assert_eq!(0, tracker.active(1301.0));
+    found.extend(tracker.current());
+
+    assert_eq!(
+        vec![
+            MultiKill {
+                start_tick: 100,
+                end_tick: 400,
+                kills: 4,
+            },
+            MultiKill {
+                start_tick: 1000,
+                end_tick: 1100,
+                kills: 2,
+            },
+        ],
+        found
+    );
+    assert_eq!("quad kill", found[0].name());
+}
@@ -0,0 +1,204 @@
+mod blender;
+mod csv;
+mod hlae;
+mod json;
+mod jsx;
+mod txt;
+
+use crate::frame::Frame;
+use crate::killfeed::KillFeedEntry;
+use crate::multikill::MultiKillMarker;
+use crate::timing::Timing;
+use crate::TickData;
+use serde::Serialize;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+
+pub use self::csv::CsvColumn;
+
+/// Name for the scene objects created by the script outputs, the file name of the output
+///
+/// Only the `.dem` extension is removed, the player suffix that follows it when extracting multiple players is kept.
+fn output_name(base_path: &str) -> String {
+    let path = Path::new(base_path);
+    let name = match path.file_name() {
+        Some(name) => name.to_string_lossy(),
+        None => return String::from("ammocount"),
+    };
+    match name.rfind(".dem") {
+        Some(index) => format!("{}{}", &name[..index], &name[index + ".dem".len()..]),
+        None => path
+            .file_stem()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+    }
+}
+
+#[test]
+fn test_output_name() {
+    assert_eq!("match", output_name("out/match.dem"));
+    assert_eq!("match_player", output_name("out/match.dem_player"));
+    assert_eq!("match_12345", output_name("match.dem_12345"));
+    assert_eq!("clip", output_name("out/clip.mp4"));
+    assert_eq!("ammocount", output_name(".."));
+}
+
+/// Absolute yaw of every frame, kept continuous instead of wrapping around at ±180 degrees
+fn continuous_yaws(frames: &[Frame]) -> Vec<f32> {
+    let mut yaws: Vec<f32> = Vec::with_capacity(frames.len());
+    for frame in frames {
+        let yaw = match yaws.last() {
+            Some(yaw) => yaw + frame.delta_angles[1],
+            None => frame.angles[1],
+        };
+        yaws.push(yaw);
+    }
+    yaws
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// One `<demo>_<channel>.txt` file per channel
+    Txt,
+    /// After Effects script creating a composition with the text layers and camera
+    Jsx,
+    /// Blender python script creating an animated camera
+    Blender,
+    /// HLAE campath following the player's view
+    Campath,
+    /// Single json document with the demo metadata and all frames
+    Json,
+    /// Newline delimited json, the demo metadata followed by one line per frame
+    Ndjson,
+    /// Csv with one row per frame
+    Csv,
+    /// Csv with the raw data for every tick
+    CsvTicks,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "txt" => Ok(OutputFormat::Txt),
+            "jsx" => Ok(OutputFormat::Jsx),
+            "blender" => Ok(OutputFormat::Blender),
+            "campath" | "hlae" => Ok(OutputFormat::Campath),
+            "json" => Ok(OutputFormat::Json),
+            "ndjson" => Ok(OutputFormat::Ndjson),
+            "csv" => Ok(OutputFormat::Csv),
+            "csv-ticks" => Ok(OutputFormat::CsvTicks),
+            _ => Err(format!(
+                "unknown output format \"{}\", expected one of txt, jsx, blender, campath, json, ndjson, csv or csv-ticks",
+                s
+            )),
+        }
+    }
+}
+
+/// Damage number tracks written by the txt output, mirroring tf2's damage number options
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DamageNumbers {
+    /// Damage of the last hit, fading out (`<demo>_hit.txt`)
+    Last,
+    /// Every hit as a floating number with the victim's position on screen (`<demo>_damagenumbers.txt`)
+    PerHit,
+    /// Damage of the hits within the batching window added together (`<demo>_batchedhit.txt`)
+    Batched,
+    /// Damage dealt since the player last died (`<demo>_lifedamage.txt`)
+    Life,
+}
+
+impl FromStr for DamageNumbers {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "last" => Ok(DamageNumbers::Last),
+            "per-hit" => Ok(DamageNumbers::PerHit),
+            "batched" => Ok(DamageNumbers::Batched),
+            "life" => Ok(DamageNumbers::Life),
+            _ => Err(format!(
+                "unknown damage number mode \"{}\", expected one of last, per-hit, batched or life",
+                s
+            )),
+        }
+    }
+}
+
+/// Information about the demo and clip, included in the structured output formats
+#[derive(Clone, Debug, Serialize)]
+pub struct Metadata {
+    pub demo: String,
+    pub map: String,
+    pub server: String,
+    pub nick: String,
+    pub duration: f32,
+    pub ticks: u32,
+    pub player: String,
+    pub start_tick: u32,
+    pub end_tick: u32,
+    pub fps: f64,
+}
+
+/// Format specific output settings
+#[derive(Clone, Debug, Default)]
+pub struct OutputOptions {
+    /// Number of frames on either side of a frame to average the campath view over, 0 disables smoothing
+    pub campath_smoothing: usize,
+    /// Columns to include in the csv output, the default columns for the csv format are used if not set
+    pub csv_columns: Option<Vec<CsvColumn>>,
+    /// Damage number tracks to write in the txt output, only the last hit if not set
+    pub damage_numbers: Option<Vec<DamageNumbers>>,
+}
+
+/// Everything extracted for a clip
+pub struct ExportData<'a> {
+    /// Resampled output frames
+    pub frames: &'a [Frame],
+    /// Kill feed entries shown during the clip
+    pub kill_feed: &'a [KillFeedEntry],
+    /// Multi kills by the player during the clip
+    pub multi_kills: &'a [MultiKillMarker],
+    /// Raw data for the ticks in the clip
+    pub ticks: &'a [TickData],
+    pub timing: &'a Timing,
+    pub metadata: &'a Metadata,
+}
+
+/// Write the extracted data in the given format, output files are named after `base_path`
+pub fn write(
+    format: OutputFormat,
+    base_path: &str,
+    data: &ExportData,
+    options: &OutputOptions,
+) -> io::Result<()> {
+    let ExportData {
+        frames,
+        kill_feed,
+        multi_kills,
+        ticks,
+        timing,
+        metadata: _,
+    } = *data;
+    match format {
+        OutputFormat::Txt => txt::write(
+            base_path,
+            frames,
+            kill_feed,
+            multi_kills,
+            options.damage_numbers.as_deref(),
+        ),
+        OutputFormat::Jsx => jsx::write(base_path, frames, kill_feed, multi_kills, timing),
+        OutputFormat::Blender => blender::write(base_path, frames, timing),
+        OutputFormat::Campath => hlae::write(base_path, frames, timing, options.campath_smoothing),
+        OutputFormat::Json => json::write(base_path, data),
+        OutputFormat::Ndjson => json::write_lines(base_path, data),
+        OutputFormat::Csv => csv::write_frames(base_path, frames, options.csv_columns.as_deref()),
+        OutputFormat::CsvTicks => {
+            csv::write_ticks(base_path, ticks, options.csv_columns.as_deref())
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use super::{continuous_yaws, output_name};
+use crate::frame::{half_fov_tan_vertical, Frame};
+use crate::timing::Timing;
+use serde::Serialize;
+use std::fs;
+use std::io::{self, BufWriter, Write};
+
+/// Hammer units are defined as one inch
+const METERS_PER_HAMMER_UNIT: f32 = 0.0254;
+
+#[derive(Serialize)]
+struct ScriptData {
+    name: String,
+    fps: u32,
+    fps_base: f64,
+    fov: f32,
+    frames: Vec<BlenderFrame>,
+}
+
+#[derive(Serialize)]
+struct BlenderFrame {
+    frame: i32,
+    /// Position in hammer units
+    location: [f32; 3],
+    /// Camera rotation in degrees
+    rotation: [f32; 3],
+    ammo: u16,
+    max_ammo: u16,
+    health: u16,
+    uber: Option<u8>,
+    hit: u32,
+}
+
+/// Write a python script that creates an animated camera with the hud values as animated custom properties
+pub fn write(base_path: &str, frames: &[Frame], timing: &Timing) -> io::Result<()> {
+    let fps = timing.frame_rate().fps();
+    // blender stores fractional frame rates as an integer rate with a divider
+    let fps_int = fps.ceil() as u32;
+    let name = output_name(base_path);
+
+    // keep the yaw continuous so blender doesn't interpolate the long way around
+    let yaws = continuous_yaws(frames);
+    let frames = frames
+        .iter()
+        .zip(yaws)
+        .map(|(frame, yaw)| {
+            // the camera looks down its local -z axis by default, both blender and tf2 use a right-handed z-up
+            // coordinate system, so only the camera itself has to be rotated to look along the x axis at zero yaw
+            BlenderFrame {
+                frame: frame.frame + 1,
+                location: [frame.position.x, frame.position.y, frame.position.z],
+                rotation: [90.0 - frame.angles[0], 0.0, yaw - 90.0],
+                ammo: frame.ammo,
+                max_ammo: frame.max_ammo,
+                health: frame.health,
+                uber: frame.uber,
+                hit: frame.hit,
+            }
+        })
+        .collect();
+
+    let data = ScriptData {
+        name,
+        fps: fps_int,
+        fps_base: fps_int as f64 / fps,
+        fov: 2.0 * half_fov_tan_vertical().atan().to_degrees(),
+        frames,
+    };
+
+    let mut out = BufWriter::new(fs::File::create(format!("{}_blender.py", base_path))?);
+    writeln!(&mut out, "import json")?;
+    writeln!(&mut out)?;
+    writeln!(
+        &mut out,
+        "# size of a hammer unit in blender units, change this if your scene uses a different scale"
+    )?;
+    writeln!(&mut out, "SCALE = {}", METERS_PER_HAMMER_UNIT)?;
+    writeln!(
+        &mut out,
+        "DATA = json.loads({})",
+        serde_json::to_string(&serde_json::to_string(&data)?)?
+    )?;
+    out.write_all(SCRIPT.as_bytes())?;
+    Ok(())
+}
+
+const SCRIPT: &str = r#"
+import bpy
+from math import radians
+
+scene = bpy.context.scene
+scene.render.fps = DATA["fps"]
+scene.render.fps_base = DATA["fps_base"]
+
+camera_data = bpy.data.cameras.new(DATA["name"])
+camera_data.sensor_fit = "VERTICAL"
+camera_data.angle = radians(DATA["fov"])
+camera_data.clip_end = 10000 * SCALE
+
+camera = bpy.data.objects.new(DATA["name"], camera_data)
+camera.rotation_mode = "XYZ"
+scene.collection.objects.link(camera)
+
+for frame in DATA["frames"]:
+    number = frame["frame"]
+    camera.location = [value * SCALE for value in frame["location"]]
+    camera.rotation_euler = [radians(value) for value in frame["rotation"]]
+    camera.keyframe_insert("location", frame=number)
+    camera.keyframe_insert("rotation_euler", frame=number)
+
+    for prop in ["ammo", "max_ammo", "health", "uber", "hit"]:
+        if frame[prop] is not None:
+            camera[prop] = frame[prop]
+            camera.keyframe_insert('["%s"]' % prop, frame=number)
+
+if DATA["frames"]:
+    scene.frame_start = DATA["frames"][0]["frame"]
+    scene.frame_end = DATA["frames"][-1]["frame"]
+scene.camera = camera
+"#;
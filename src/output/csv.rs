@@ -0,0 +1,327 @@
+use crate::analyser::weapon_name;
+use crate::frame::Frame;
+use crate::TickData;
+use ::csv::Writer;
+use std::fmt::Display;
+use std::io;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CsvColumn {
+    Frame,
+    Tick,
+    Ammo,
+    MaxAmmo,
+    Health,
+    Uber,
+    Weapon,
+    Hit,
+    Airshot,
+    HitWeapon,
+    Kills,
+    UberDrops,
+    LifeKills,
+    ClipKills,
+    MultiKill,
+    BatchedHit,
+    LifeDamage,
+    X,
+    Y,
+    Z,
+    Pitch,
+    Yaw,
+    PitchDelta,
+    YawDelta,
+    CameraX,
+    CameraY,
+    CameraZ,
+    CameraRotationX,
+    CameraRotationY,
+    CameraRotationZ,
+}
+
+impl CsvColumn {
+    const ALL: &'static [CsvColumn] = &[
+        CsvColumn::Frame,
+        CsvColumn::Tick,
+        CsvColumn::Ammo,
+        CsvColumn::MaxAmmo,
+        CsvColumn::Health,
+        CsvColumn::Uber,
+        CsvColumn::Weapon,
+        CsvColumn::Hit,
+        CsvColumn::Airshot,
+        CsvColumn::HitWeapon,
+        CsvColumn::Kills,
+        CsvColumn::UberDrops,
+        CsvColumn::LifeKills,
+        CsvColumn::ClipKills,
+        CsvColumn::MultiKill,
+        CsvColumn::BatchedHit,
+        CsvColumn::LifeDamage,
+        CsvColumn::X,
+        CsvColumn::Y,
+        CsvColumn::Z,
+        CsvColumn::Pitch,
+        CsvColumn::Yaw,
+        CsvColumn::PitchDelta,
+        CsvColumn::YawDelta,
+        CsvColumn::CameraX,
+        CsvColumn::CameraY,
+        CsvColumn::CameraZ,
+        CsvColumn::CameraRotationX,
+        CsvColumn::CameraRotationY,
+        CsvColumn::CameraRotationZ,
+    ];
+
+    /// Columns available for the resampled frames
+    const FRAME_COLUMNS: &'static [CsvColumn] = &[
+        CsvColumn::Frame,
+        CsvColumn::Tick,
+        CsvColumn::Ammo,
+        CsvColumn::MaxAmmo,
+        CsvColumn::Health,
+        CsvColumn::Uber,
+        CsvColumn::Weapon,
+        CsvColumn::Hit,
+        CsvColumn::Airshot,
+        CsvColumn::HitWeapon,
+        CsvColumn::LifeKills,
+        CsvColumn::ClipKills,
+        CsvColumn::MultiKill,
+        CsvColumn::BatchedHit,
+        CsvColumn::LifeDamage,
+        CsvColumn::X,
+        CsvColumn::Y,
+        CsvColumn::Z,
+        CsvColumn::Pitch,
+        CsvColumn::Yaw,
+        CsvColumn::PitchDelta,
+        CsvColumn::YawDelta,
+        CsvColumn::CameraX,
+        CsvColumn::CameraY,
+        CsvColumn::CameraZ,
+        CsvColumn::CameraRotationX,
+        CsvColumn::CameraRotationY,
+        CsvColumn::CameraRotationZ,
+    ];
+
+    /// Columns available for the raw per-tick data
+    const TICK_COLUMNS: &'static [CsvColumn] = &[
+        CsvColumn::Tick,
+        CsvColumn::Ammo,
+        CsvColumn::MaxAmmo,
+        CsvColumn::Health,
+        CsvColumn::Uber,
+        CsvColumn::Weapon,
+        CsvColumn::Hit,
+        CsvColumn::Airshot,
+        CsvColumn::HitWeapon,
+        CsvColumn::Kills,
+        CsvColumn::UberDrops,
+        CsvColumn::LifeKills,
+        CsvColumn::LifeDamage,
+        CsvColumn::X,
+        CsvColumn::Y,
+        CsvColumn::Z,
+        CsvColumn::Pitch,
+        CsvColumn::Yaw,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            CsvColumn::Frame => "frame",
+            CsvColumn::Tick => "tick",
+            CsvColumn::Ammo => "ammo",
+            CsvColumn::MaxAmmo => "max_ammo",
+            CsvColumn::Health => "health",
+            CsvColumn::Uber => "uber",
+            CsvColumn::Weapon => "weapon",
+            CsvColumn::Hit => "hit",
+            CsvColumn::Airshot => "airshot",
+            CsvColumn::HitWeapon => "hit_weapon",
+            CsvColumn::Kills => "kills",
+            CsvColumn::UberDrops => "uber_drops",
+            CsvColumn::LifeKills => "life_kills",
+            CsvColumn::ClipKills => "clip_kills",
+            CsvColumn::MultiKill => "multi_kill",
+            CsvColumn::BatchedHit => "batched_hit",
+            CsvColumn::LifeDamage => "life_damage",
+            CsvColumn::X => "x",
+            CsvColumn::Y => "y",
+            CsvColumn::Z => "z",
+            CsvColumn::Pitch => "pitch",
+            CsvColumn::Yaw => "yaw",
+            CsvColumn::PitchDelta => "pitch_delta",
+            CsvColumn::YawDelta => "yaw_delta",
+            CsvColumn::CameraX => "camera_x",
+            CsvColumn::CameraY => "camera_y",
+            CsvColumn::CameraZ => "camera_z",
+            CsvColumn::CameraRotationX => "camera_rotation_x",
+            CsvColumn::CameraRotationY => "camera_rotation_y",
+            CsvColumn::CameraRotationZ => "camera_rotation_z",
+        }
+    }
+
+    /// Value of the column for a resampled frame, `None` if the column only exists for the raw tick data
+    fn frame_value(self, frame: &Frame) -> Option<String> {
+        Some(match self {
+            CsvColumn::Frame => frame.frame.to_string(),
+            CsvColumn::Tick => frame.tick.to_string(),
+            CsvColumn::Ammo => frame.ammo.to_string(),
+            CsvColumn::MaxAmmo => frame.max_ammo.to_string(),
+            CsvColumn::Health => frame.health.to_string(),
+            CsvColumn::Uber => optional(frame.uber),
+            CsvColumn::Weapon => frame.weapon.clone(),
+            CsvColumn::Hit => frame.hit.to_string(),
+            CsvColumn::Airshot => frame.airshot.to_string(),
+            CsvColumn::HitWeapon => frame.hit_weapon.clone(),
+            CsvColumn::LifeKills => frame.life_kills.to_string(),
+            CsvColumn::ClipKills => frame.clip_kills.to_string(),
+            CsvColumn::MultiKill => frame.multi_kill.to_string(),
+            CsvColumn::BatchedHit => frame.batched_hit.to_string(),
+            CsvColumn::LifeDamage => frame.life_damage.to_string(),
+            CsvColumn::X => frame.position.x.to_string(),
+            CsvColumn::Y => frame.position.y.to_string(),
+            CsvColumn::Z => frame.position.z.to_string(),
+            CsvColumn::Pitch => frame.angles[0].to_string(),
+            CsvColumn::Yaw => frame.angles[1].to_string(),
+            CsvColumn::PitchDelta => frame.delta_angles[0].to_string(),
+            CsvColumn::YawDelta => frame.delta_angles[1].to_string(),
+            CsvColumn::CameraX => frame.camera.position.x.to_string(),
+            CsvColumn::CameraY => frame.camera.position.y.to_string(),
+            CsvColumn::CameraZ => frame.camera.position.z.to_string(),
+            CsvColumn::CameraRotationX => frame.camera.angle[0].to_string(),
+            CsvColumn::CameraRotationY => frame.camera.angle[1].to_string(),
+            CsvColumn::CameraRotationZ => frame.camera.angle[2].to_string(),
+            _ => return None,
+        })
+    }
+
+    /// Check that all columns are available for the resampled frames
+    pub fn check_frame_columns(columns: &[CsvColumn]) -> Result<(), String> {
+        check_available(columns, CsvColumn::FRAME_COLUMNS, "resampled frames")
+    }
+
+    /// Check that all columns are available for the raw per-tick data
+    pub fn check_tick_columns(columns: &[CsvColumn]) -> Result<(), String> {
+        check_available(columns, CsvColumn::TICK_COLUMNS, "per-tick data")
+    }
+
+    /// Value of the column for the raw tick data, `None` if the column only exists for the resampled frames
+    fn tick_value(self, tick: &TickData) -> Option<String> {
+        Some(match self {
+            CsvColumn::Tick => tick.tick.to_string(),
+            CsvColumn::Ammo => tick.ammo.to_string(),
+            CsvColumn::MaxAmmo => tick.max_ammo.to_string(),
+            CsvColumn::Health => tick.health.to_string(),
+            CsvColumn::Uber => optional(tick.uber),
+            CsvColumn::Weapon => tick.weapon.clone(),
+            CsvColumn::Hit => optional(tick.hit),
+            CsvColumn::Airshot => tick.airshot.to_string(),
+            CsvColumn::HitWeapon => tick.hit_weapon.map(weapon_name).unwrap_or_default(),
+            CsvColumn::Kills => tick.kills.to_string(),
+            CsvColumn::UberDrops => tick.uber_drops.to_string(),
+            CsvColumn::LifeKills => tick.life_kills.to_string(),
+            CsvColumn::LifeDamage => tick.life_damage.to_string(),
+            CsvColumn::X => tick.position.x.to_string(),
+            CsvColumn::Y => tick.position.y.to_string(),
+            CsvColumn::Z => tick.position.z.to_string(),
+            CsvColumn::Pitch => optional(tick.angles[0]),
+            CsvColumn::Yaw => optional(tick.angles[1]),
+            _ => return None,
+        })
+    }
+}
+
+impl FromStr for CsvColumn {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim().to_ascii_lowercase();
+        CsvColumn::ALL
+            .iter()
+            .copied()
+            .find(|column| column.name() == name)
+            .ok_or_else(|| {
+                let names: Vec<_> = CsvColumn::ALL.iter().map(|column| column.name()).collect();
+                format!(
+                    "unknown csv column \"{}\", expected one of {}",
+                    s,
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+#[test]
+fn test_parse_columns() {
+    assert_eq!(Ok(CsvColumn::MaxAmmo), "max_ammo".parse());
+    assert_eq!(Ok(CsvColumn::Weapon), "Weapon".parse());
+    assert!("armor".parse::<CsvColumn>().is_err());
+}
+
+#[test]
+fn test_check_columns() {
+    assert!(CsvColumn::check_tick_columns(&[CsvColumn::Tick, CsvColumn::Yaw]).is_ok());
+    assert!(CsvColumn::check_tick_columns(&[CsvColumn::Tick, CsvColumn::Frame]).is_err());
+    assert!(CsvColumn::check_frame_columns(&[CsvColumn::Frame, CsvColumn::Kills]).is_err());
+}
+
+fn check_available(
+    columns: &[CsvColumn],
+    available: &[CsvColumn],
+    data: &str,
+) -> Result<(), String> {
+    match columns.iter().find(|column| !available.contains(column)) {
+        Some(column) => Err(format!(
+            "csv column \"{}\" is not available for the {}",
+            column.name(),
+            data
+        )),
+        None => Ok(()),
+    }
+}
+
+fn optional<T: Display>(value: Option<T>) -> String {
+    value.map(|value| value.to_string()).unwrap_or_default()
+}
+
+/// Write the resampled frames with one row per frame
+pub fn write_frames(
+    base_path: &str,
+    frames: &[Frame],
+    columns: Option<&[CsvColumn]>,
+) -> io::Result<()> {
+    let columns = columns.unwrap_or(CsvColumn::FRAME_COLUMNS);
+    CsvColumn::check_frame_columns(columns)
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))?;
+    let mut writer = Writer::from_path(format!("{}.csv", base_path))?;
+    writer.write_record(columns.iter().map(|column| column.name()))?;
+    for frame in frames {
+        writer.write_record(
+            columns
+                .iter()
+                .filter_map(|column| column.frame_value(frame)),
+        )?;
+    }
+    writer.flush()
+}
+
+/// Write the raw per-tick data with one row per tick
+pub fn write_ticks(
+    base_path: &str,
+    ticks: &[TickData],
+    columns: Option<&[CsvColumn]>,
+) -> io::Result<()> {
+    let columns = columns.unwrap_or(CsvColumn::TICK_COLUMNS);
+    CsvColumn::check_tick_columns(columns)
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))?;
+
+    let mut writer = Writer::from_path(format!("{}_ticks.csv", base_path))?;
+    writer.write_record(columns.iter().map(|column| column.name()))?;
+    for tick in ticks {
+        writer.write_record(columns.iter().filter_map(|column| column.tick_value(tick)))?;
+    }
+    writer.flush()
+}
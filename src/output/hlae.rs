@@ -0,0 +1,75 @@
+use super::continuous_yaws;
+use crate::frame::{Frame, DEFAULT_FOV};
+use crate::timing::Timing;
+use std::fs;
+use std::io::{self, BufWriter, Write};
+
+/// Write a HLAE campath that follows the player's view, optionally smoothing the view over `smoothing` frames
+///
+/// The campath can be loaded with `mirv_campath load <file>`
+pub fn write(
+    base_path: &str,
+    frames: &[Frame],
+    timing: &Timing,
+    smoothing: usize,
+) -> io::Result<()> {
+    // keep the yaw continuous so the smoothing doesn't average over the edge
+    let yaws = continuous_yaws(frames);
+
+    let x = smooth(frames.iter().map(|frame| frame.position.x), smoothing);
+    let y = smooth(frames.iter().map(|frame| frame.position.y), smoothing);
+    let z = smooth(frames.iter().map(|frame| frame.position.z), smoothing);
+    let pitch = smooth(frames.iter().map(|frame| frame.angles[0]), smoothing);
+    let yaw = smooth(yaws, smoothing);
+
+    let mut out = BufWriter::new(fs::File::create(format!("{}_campath.xml", base_path))?);
+    writeln!(&mut out, r#"<?xml version="1.0" encoding="utf-8"?>"#)?;
+    writeln!(&mut out, "<campath>")?;
+    writeln!(&mut out, "\t<points>")?;
+    for (i, frame) in frames.iter().enumerate() {
+        writeln!(
+            &mut out,
+            r#"		<p t="{}" x="{}" y="{}" z="{}" fov="{}" rx="0" ry="{}" rz="{}"/>"#,
+            timing.demo_time(frame.tick),
+            x[i],
+            y[i],
+            z[i],
+            DEFAULT_FOV,
+            pitch[i],
+            yaw[i]
+        )?;
+    }
+    writeln!(&mut out, "\t</points>")?;
+    writeln!(&mut out, "</campath>")?;
+    Ok(())
+}
+
+/// Centered moving average over `radius` values on either side, the window is shrunk near the edges
+fn smooth(values: impl IntoIterator<Item = f32>, radius: usize) -> Vec<f32> {
+    let values: Vec<f32> = values.into_iter().collect();
+    if radius == 0 {
+        return values;
+    }
+    (0..values.len())
+        .map(|i| {
+            let radius = radius.min(i).min(values.len() - 1 - i);
+            let window = &values[i - radius..=i + radius];
+            window.iter().sum::<f32>() / window.len() as f32
+        })
+        .collect()
+}
+
+#[test]
+fn test_smooth() {
+    assert_eq!(vec![1.0, 2.0, 6.0], smooth(vec![1.0, 2.0, 6.0], 0));
+    assert_eq!(vec![1.0, 3.0, 6.0], smooth(vec![1.0, 2.0, 6.0], 1));
+    assert_eq!(
+        vec![0.0, 1.0, 2.0, 3.0, 4.0],
+        smooth(vec![0.0, 1.0, 2.0, 3.0, 4.0], 2)
+    );
+    assert_eq!(
+        vec![0.0, 10.0 / 3.0, 20.0 / 3.0, 10.0, 10.0],
+        smooth(vec![0.0, 0.0, 10.0, 10.0, 10.0], 1)
+    );
+    assert_eq!(Vec::<f32>::new(), smooth(vec![], 3));
+}
@@ -0,0 +1,58 @@
+use super::{ExportData, Metadata};
+use crate::frame::Frame;
+use crate::killfeed::KillFeedEntry;
+use crate::multikill::MultiKillMarker;
+use serde::Serialize;
+use std::fs;
+use std::io::{self, BufWriter, Write};
+
+#[derive(Serialize)]
+struct Document<'a> {
+    header: &'a Metadata,
+    frames: &'a [Frame],
+    kill_feed: &'a [KillFeedEntry],
+    multi_kills: &'a [MultiKillMarker],
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Record<'a> {
+    Header(&'a Metadata),
+    Frame(&'a Frame),
+    Kill(&'a KillFeedEntry),
+    MultiKill(&'a MultiKillMarker),
+}
+
+/// Write a single json document containing the metadata, all frames, the kill feed and the multi kills
+pub fn write(base_path: &str, data: &ExportData) -> io::Result<()> {
+    let mut out = BufWriter::new(fs::File::create(format!("{}.json", base_path))?);
+    serde_json::to_writer(
+        &mut out,
+        &Document {
+            header: data.metadata,
+            frames: data.frames,
+            kill_feed: data.kill_feed,
+            multi_kills: data.multi_kills,
+        },
+    )?;
+    out.flush()
+}
+
+/// Write newline delimited json, a header record with the metadata followed by a record for every frame,
+/// every kill feed entry and every multi kill
+pub fn write_lines(base_path: &str, data: &ExportData) -> io::Result<()> {
+    let mut out = BufWriter::new(fs::File::create(format!("{}.ndjson", base_path))?);
+    let records = data
+        .frames
+        .iter()
+        .map(Record::Frame)
+        .chain(data.kill_feed.iter().map(Record::Kill))
+        .chain(data.multi_kills.iter().map(Record::MultiKill));
+    serde_json::to_writer(&mut out, &Record::Header(data.metadata))?;
+    writeln!(&mut out)?;
+    for record in records {
+        serde_json::to_writer(&mut out, &record)?;
+        writeln!(&mut out)?;
+    }
+    out.flush()
+}
@@ -0,0 +1,310 @@
+use super::output_name;
+use crate::frame::{half_fov_tan_vertical, view_offset, Frame};
+use crate::killfeed::{Crit, KillFeedEntry, KillType};
+use crate::multikill::MultiKillMarker;
+use crate::timing::Timing;
+use serde::Serialize;
+use std::fs;
+use std::io::{self, BufWriter, Write};
+use tf_demo_parser::demo::vector::Vector;
+
+const WIDTH: u32 = 1920;
+const HEIGHT: u32 = 1080;
+
+#[derive(Serialize)]
+struct ScriptData {
+    name: String,
+    width: u32,
+    height: u32,
+    fps: f64,
+    duration: f64,
+    text: Vec<TextLayer>,
+    kill_feed: Vec<KillFeedLayer>,
+    markers: Vec<Marker>,
+    camera: CameraLayer,
+}
+
+/// Composition marker
+#[derive(Serialize)]
+struct Marker {
+    time: f64,
+    comment: &'static str,
+}
+
+/// Text layer for a single kill feed entry, shown from `in_time` until `out_time`
+#[derive(Serialize)]
+struct KillFeedLayer {
+    text: String,
+    in_time: f64,
+    out_time: f64,
+    /// Number of older entries still shown when this one appears
+    slot: usize,
+    attacker_color: Option<&'static str>,
+    victim_color: &'static str,
+}
+
+impl KillFeedLayer {
+    fn new(entry: &KillFeedEntry, slot: usize, fps: f64) -> Self {
+        let kill = &entry.kill;
+        let mut icon = kill.weapon.clone();
+        match kill.kill_type {
+            KillType::Headshot => icon.push_str(" (headshot)"),
+            KillType::Backstab => icon.push_str(" (backstab)"),
+            _ => {}
+        }
+        match kill.crit {
+            Crit::Full => icon.push_str(" (crit)"),
+            Crit::Mini => icon.push_str(" (mini crit)"),
+            Crit::None => {}
+        }
+        let attacker = match (&kill.attacker, &kill.assister) {
+            (Some(attacker), Some(assister)) => {
+                format!("{} + {}  ", attacker.name, assister.name)
+            }
+            (Some(attacker), None) => format!("{}  ", attacker.name),
+            (None, _) => String::new(),
+        };
+        KillFeedLayer {
+            text: format!("{}[{}]  {}", attacker, icon, kill.victim.name),
+            in_time: entry.appear as f64 / fps,
+            out_time: entry.expire as f64 / fps,
+            slot,
+            attacker_color: kill.attacker.as_ref().map(|attacker| attacker.color),
+            victim_color: kill.victim.color,
+        }
+    }
+}
+
+/// Source text keyframes, only set when the text changes
+#[derive(Serialize)]
+struct TextLayer {
+    name: &'static str,
+    times: Vec<f64>,
+    values: Vec<String>,
+}
+
+impl TextLayer {
+    fn new(name: &'static str) -> Self {
+        TextLayer {
+            name,
+            times: Vec::new(),
+            values: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, time: f64, value: String) {
+        if self.values.last() != Some(&value) {
+            self.times.push(time);
+            self.values.push(value);
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct CameraLayer {
+    zoom: f32,
+    times: Vec<f64>,
+    position: Vec<[f32; 3]>,
+    x_rotation: Vec<f32>,
+    y_rotation: Vec<f32>,
+    z_rotation: Vec<f32>,
+}
+
+/// Position of the AE camera for a camera movement relative to the start of the clip
+///
+/// The camera starts at the default position of an AE camera, looking along the z axis.
+/// Like the camera rotation, the movement is relative to the view at the start of the clip,
+/// AE uses x right, y down, z forward.
+fn camera_position(start_angles: [f32; 2], movement: Vector, zoom: f32) -> [f32; 3] {
+    let [forward, right, up] = view_offset(start_angles, movement);
+    [
+        WIDTH as f32 / 2.0 + right,
+        HEIGHT as f32 / 2.0 - up,
+        -zoom + forward,
+    ]
+}
+
+#[test]
+fn test_camera_position() {
+    let movement = |x, y, z| Vector { x, y, z };
+    let center = [WIDTH as f32 / 2.0, HEIGHT as f32 / 2.0, -100.0];
+    let assert_position = |expected: [f32; 3], actual: [f32; 3]| {
+        for (expected, actual) in expected.iter().zip(actual.iter()) {
+            assert!(
+                (expected - actual).abs() < 0.001,
+                "{:?} {:?}",
+                expected,
+                actual
+            );
+        }
+    };
+
+    assert_position(
+        [center[0], center[1], center[2] + 50.0],
+        camera_position([0.0, 0.0], movement(50.0, 0.0, 0.0), 100.0),
+    );
+    // starting the clip facing along the y axis, moving along y is moving forward and along x is moving right
+    assert_position(
+        [center[0], center[1], center[2] + 50.0],
+        camera_position([0.0, 90.0], movement(0.0, 50.0, 0.0), 100.0),
+    );
+    assert_position(
+        [center[0] + 50.0, center[1], center[2]],
+        camera_position([0.0, 90.0], movement(50.0, 0.0, 0.0), 100.0),
+    );
+    assert_position(
+        [center[0], center[1] - 50.0, center[2]],
+        camera_position([0.0, 90.0], movement(0.0, 0.0, 50.0), 100.0),
+    );
+}
+
+/// Write an After Effects script that creates a composition with text layers for the hud values and an animated camera
+pub fn write(
+    base_path: &str,
+    frames: &[Frame],
+    kill_feed: &[KillFeedEntry],
+    multi_kills: &[MultiKillMarker],
+    timing: &Timing,
+) -> io::Result<()> {
+    let fps = timing.frame_rate().fps();
+    let name = output_name(base_path);
+    // camera zoom that matches tf2's default fov
+    let zoom = HEIGHT as f32 / 2.0 / half_fov_tan_vertical();
+    // the camera moves relative to the view of the first frame, the start of the clip
+    let start_angles = frames.first().map_or([0.0, 0.0], |frame| frame.angles);
+
+    let mut ammo = TextLayer::new("Ammo");
+    let mut health = TextLayer::new("Health");
+    let mut uber = TextLayer::new("Uber");
+    let mut weapon = TextLayer::new("Weapon");
+    let mut life_kills = TextLayer::new("Kills");
+    let mut clip_kills = TextLayer::new("Clip kills");
+    let mut camera = CameraLayer {
+        zoom,
+        times: Vec::with_capacity(frames.len()),
+        position: Vec::with_capacity(frames.len()),
+        x_rotation: Vec::with_capacity(frames.len()),
+        y_rotation: Vec::with_capacity(frames.len()),
+        z_rotation: Vec::with_capacity(frames.len()),
+    };
+
+    let mut markers = Vec::new();
+    let mut last_airshot = false;
+    for frame in frames {
+        let time = frame.frame as f64 / fps;
+        if frame.airshot && !last_airshot {
+            markers.push(Marker {
+                time,
+                comment: "airshot",
+            });
+        }
+        last_airshot = frame.airshot;
+        ammo.push(time, format!("{}/{}", frame.ammo, frame.max_ammo));
+        health.push(time, frame.health.to_string());
+        if let Some(value) = frame.uber {
+            uber.push(time, value.to_string());
+        }
+        weapon.push(time, frame.weapon.clone());
+        life_kills.push(time, frame.life_kills.to_string());
+        clip_kills.push(time, frame.clip_kills.to_string());
+
+        camera.times.push(time);
+        camera
+            .position
+            .push(camera_position(start_angles, frame.camera.position, zoom));
+        camera.x_rotation.push(frame.camera.angle[0]);
+        camera.y_rotation.push(frame.camera.angle[1]);
+        camera.z_rotation.push(frame.camera.angle[2]);
+    }
+
+    let duration = frames
+        .last()
+        .map(|frame| (frame.frame + 1) as f64 / fps)
+        .unwrap_or(1.0 / fps);
+
+    let text = vec![ammo, health, uber, weapon, life_kills, clip_kills]
+        .into_iter()
+        .filter(|layer| !layer.values.is_empty())
+        .collect();
+
+    let kill_feed = kill_feed
+        .iter()
+        .enumerate()
+        .map(|(index, entry)| {
+            let slot = kill_feed[..index]
+                .iter()
+                .filter(|older| older.expire > entry.appear)
+                .count();
+            KillFeedLayer::new(entry, slot, fps)
+        })
+        .collect();
+
+    markers.extend(multi_kills.iter().map(|marker| Marker {
+        time: marker.end_frame as f64 / fps,
+        comment: marker.name,
+    }));
+
+    let data = ScriptData {
+        name,
+        width: WIDTH,
+        height: HEIGHT,
+        fps,
+        duration,
+        text,
+        kill_feed,
+        markers,
+        camera,
+    };
+
+    let mut out = BufWriter::new(fs::File::create(format!("{}.jsx", base_path))?);
+    writeln!(&mut out, "(function () {{")?;
+    writeln!(
+        &mut out,
+        "    var data = {};",
+        serde_json::to_string(&data)?
+    )?;
+    out.write_all(SCRIPT.as_bytes())?;
+    writeln!(&mut out, "}})();")?;
+    Ok(())
+}
+
+const SCRIPT: &str = r#"
+    app.beginUndoGroup("Import " + data.name);
+    var comp = app.project.items.addComp(data.name, data.width, data.height, 1, data.duration, data.fps);
+
+    for (var i = 0; i < data.text.length; i++) {
+        var track = data.text[i];
+        var layer = comp.layers.addText(track.values[0]);
+        layer.name = track.name;
+        var documents = [];
+        for (var j = 0; j < track.values.length; j++) {
+            documents.push(new TextDocument(track.values[j]));
+        }
+        layer.property("ADBE Text Properties").property("ADBE Text Document").setValuesAtTimes(track.times, documents);
+    }
+
+    for (var i = 0; i < data.kill_feed.length; i++) {
+        var kill = data.kill_feed[i];
+        var layer = comp.layers.addText(kill.text);
+        layer.name = "Kill " + (i + 1);
+        layer.inPoint = Math.max(kill.in_time, 0);
+        layer.outPoint = Math.min(kill.out_time, data.duration);
+        layer.property("ADBE Transform Group").property("ADBE Position").setValue([data.width - 400, 60 + kill.slot * 40]);
+    }
+
+    for (var i = 0; i < data.markers.length; i++) {
+        comp.markerProperty.setValueAtTime(data.markers[i].time, new MarkerValue(data.markers[i].comment));
+    }
+
+    var camera = comp.layers.addCamera("POV", [data.width / 2, data.height / 2]);
+    camera.autoOrient = AutoOrientType.NO_AUTO_ORIENT;
+    camera.property("ADBE Camera Options Group").property("ADBE Camera Zoom").setValue(data.camera.zoom);
+    var transform = camera.property("ADBE Transform Group");
+    transform.property("ADBE Position").setValuesAtTimes(data.camera.times, data.camera.position);
+    transform.property("ADBE Rotate X").setValuesAtTimes(data.camera.times, data.camera.x_rotation);
+    transform.property("ADBE Rotate Y").setValuesAtTimes(data.camera.times, data.camera.y_rotation);
+    transform.property("ADBE Rotate Z").setValuesAtTimes(data.camera.times, data.camera.z_rotation);
+
+    comp.openInViewer();
+    app.endUndoGroup();
+"#;
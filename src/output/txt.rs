@@ -0,0 +1,159 @@
+use super::DamageNumbers;
+use crate::frame::Frame;
+use crate::killfeed::KillFeedEntry;
+use crate::multikill::MultiKillMarker;
+use std::fs;
+use std::io::{self, BufWriter, Write};
+
+fn create(base_path: &str, channel: &str) -> io::Result<BufWriter<fs::File>> {
+    let mut out = BufWriter::new(fs::File::create(format!("{}_{}.txt", base_path, channel))?);
+    writeln!(&mut out, "txt = []")?;
+    Ok(out)
+}
+
+pub fn write(
+    base_path: &str,
+    frames: &[Frame],
+    kill_feed: &[KillFeedEntry],
+    multi_kills: &[MultiKillMarker],
+    damage_numbers: Option<&[DamageNumbers]>,
+) -> io::Result<()> {
+    let damage_numbers = damage_numbers.unwrap_or(&[DamageNumbers::Last]);
+    let create_if = |mode: DamageNumbers, channel: &str| {
+        damage_numbers
+            .contains(&mode)
+            .then(|| create(base_path, channel))
+            .transpose()
+    };
+
+    let mut ammo_out = create(base_path, "ammo")?;
+    let mut health_out = create(base_path, "health")?;
+    let mut pitch_out = create(base_path, "pitch")?;
+    let mut yaw_out = create(base_path, "yaw")?;
+    let mut hit_out = create_if(DamageNumbers::Last, "hit")?;
+    let mut damage_numbers_out = create_if(DamageNumbers::PerHit, "damagenumbers")?;
+    let mut batched_hit_out = create_if(DamageNumbers::Batched, "batchedhit")?;
+    let mut life_damage_out = create_if(DamageNumbers::Life, "lifedamage")?;
+    let mut weapon_out = create(base_path, "weapon")?;
+    let mut camera_out = create(base_path, "camera")?;
+    let mut life_kills_out = create(base_path, "lifekills")?;
+    let mut clip_kills_out = create(base_path, "clipkills")?;
+    let mut multi_kill_out = create(base_path, "multikill")?;
+    let mut airshot_out = create(base_path, "airshot")?;
+    let mut hit_weapon_out = create(base_path, "hitweapon")?;
+    let mut uber_out = None;
+    let mut kill_feed_out = if kill_feed.is_empty() {
+        None
+    } else {
+        Some(create(base_path, "killfeed")?)
+    };
+
+    for frame in frames {
+        let index = frame.frame;
+        if let Some(uber) = frame.uber {
+            let uber_out = match &mut uber_out {
+                Some(uber_out) => uber_out,
+                None => uber_out.insert(create(base_path, "uber")?),
+            };
+            writeln!(uber_out, "txt[{}] = \"{}\";", index, uber)?;
+        }
+        writeln!(
+            &mut ammo_out,
+            "txt[{}] = \"{}/{}\";",
+            index, frame.ammo, frame.max_ammo
+        )?;
+        writeln!(&mut health_out, "txt[{}] = \"{}\";", index, frame.health)?;
+        writeln!(
+            &mut pitch_out,
+            r#"txt[{}] = {};"#,
+            index, frame.delta_angles[0]
+        )?;
+        writeln!(
+            &mut yaw_out,
+            r#"txt[{}] = {};"#,
+            index, frame.delta_angles[1]
+        )?;
+        if let Some(hit_out) = &mut hit_out {
+            writeln!(hit_out, r#"txt[{}] = {};"#, index, frame.hit)?;
+        }
+        if let Some(damage_numbers_out) = &mut damage_numbers_out {
+            writeln!(
+                damage_numbers_out,
+                r#"txt[{}] = {};"#,
+                index,
+                serde_json::to_string(&frame.damage_numbers)?
+            )?;
+        }
+        if let Some(batched_hit_out) = &mut batched_hit_out {
+            writeln!(
+                batched_hit_out,
+                r#"txt[{}] = {};"#,
+                index, frame.batched_hit
+            )?;
+        }
+        if let Some(life_damage_out) = &mut life_damage_out {
+            writeln!(
+                life_damage_out,
+                r#"txt[{}] = {};"#,
+                index, frame.life_damage
+            )?;
+        }
+        writeln!(&mut airshot_out, r#"txt[{}] = {};"#, index, frame.airshot)?;
+        writeln!(
+            &mut hit_weapon_out,
+            r#"txt[{}] = "{}";"#,
+            index, frame.hit_weapon
+        )?;
+        writeln!(&mut weapon_out, r#"txt[{}] = "{}";"#, index, frame.weapon)?;
+        writeln!(
+            &mut life_kills_out,
+            r#"txt[{}] = {};"#,
+            index, frame.life_kills
+        )?;
+        writeln!(
+            &mut clip_kills_out,
+            r#"txt[{}] = {};"#,
+            index, frame.clip_kills
+        )?;
+        writeln!(
+            &mut multi_kill_out,
+            r#"txt[{}] = {};"#,
+            index, frame.multi_kill
+        )?;
+        if let Some(kill_feed_out) = &mut kill_feed_out {
+            // all kills shown in the kill feed at this frame, oldest first
+            let shown: Vec<_> = kill_feed
+                .iter()
+                .filter(|entry| entry.appear <= index && index < entry.expire)
+                .map(|entry| &entry.kill)
+                .collect();
+            writeln!(
+                kill_feed_out,
+                r#"txt[{}] = {};"#,
+                index,
+                serde_json::to_string(&shown)?
+            )?;
+        }
+        writeln!(
+            &mut camera_out,
+            r#"txt[{}] = {};"#,
+            index,
+            serde_json::to_string(&frame.camera)?
+        )?;
+    }
+
+    // only the frames of the last kill of every multi kill are set
+    if multi_kills.is_empty() {
+        return Ok(());
+    }
+    let mut markers_out = create(base_path, "markers")?;
+    for marker in multi_kills {
+        writeln!(
+            &mut markers_out,
+            r#"txt[{}] = "{}";"#,
+            marker.end_frame, marker.name
+        )?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,195 @@
+use crate::analyser::TEAM_PROP;
+use crate::Error;
+use serde::Serialize;
+use std::convert::TryFrom;
+use steamid_ng::SteamID;
+use tf_demo_parser::demo::data::UserInfo;
+use tf_demo_parser::demo::message::packetentities::EntityId;
+use tf_demo_parser::demo::message::Message;
+use tf_demo_parser::demo::packet::message::MessagePacketMeta;
+use tf_demo_parser::demo::packet::stringtable::StringTableEntry;
+use tf_demo_parser::demo::parser::analyser::UserId;
+use tf_demo_parser::demo::parser::gamestateanalyser::{Class, Team};
+use tf_demo_parser::demo::parser::MessageHandler;
+use tf_demo_parser::demo::sendprop::{SendPropIdentifier, SendPropValue};
+use tf_demo_parser::{Demo, DemoParser, MessageType, ParserState};
+
+const CLASS_PROP: SendPropIdentifier =
+    SendPropIdentifier::new("DT_TFPlayerClassShared", "m_iClass");
+
+/// A player that was connected to the server at some point during the demo
+///
+/// Players that reconnect are listed once for every connection.
+#[derive(Clone, Debug, Serialize)]
+pub struct PlayerInfo {
+    pub name: String,
+    /// SteamID64, `None` for bots
+    pub steam_id: Option<String>,
+    /// SteamID3 (`[U:1:...]`), `None` for bots
+    pub steam_id3: Option<String>,
+    /// The last team the player was on
+    pub team: Team,
+    /// All classes played, in the order they were first played
+    pub classes: Vec<Class>,
+    pub entity_id: EntityId,
+    pub user_id: UserId,
+    /// Tick the player connected, or `0` if the player was connected when the demo started
+    pub join_tick: u32,
+    /// Tick the player disconnected, `None` if the player was connected until the end of the demo
+    pub leave_tick: Option<u32>,
+}
+
+impl PlayerInfo {
+    /// Name of the last team the player was on
+    pub fn team_name(&self) -> &'static str {
+        match self.team {
+            Team::Red => "red",
+            Team::Blue => "blue",
+            Team::Spectator => "spectator",
+            _ => "",
+        }
+    }
+
+    /// Names of the classes played
+    pub fn class_names(&self) -> Vec<&'static str> {
+        self.classes.iter().copied().map(class_name).collect()
+    }
+}
+
+fn class_name(class: Class) -> &'static str {
+    match class {
+        Class::Scout => "scout",
+        Class::Sniper => "sniper",
+        Class::Soldier => "soldier",
+        Class::Demoman => "demoman",
+        Class::Medic => "medic",
+        Class::Heavy => "heavy",
+        Class::Pyro => "pyro",
+        Class::Spy => "spy",
+        Class::Engineer => "engineer",
+        _ => "other",
+    }
+}
+
+/// List all players in the demo, ordered by entity id and connection time
+pub fn list_players(demo: &[u8]) -> Result<Vec<PlayerInfo>, Error> {
+    let demo = Demo::new(demo);
+    let parser = DemoParser::new_with_analyser(demo.get_stream(), PlayerListHandler::default());
+    let (_, mut players) = parser.parse()?;
+    players.sort_by_key(|player| (player.entity_id, player.join_tick));
+    Ok(players)
+}
+
+#[derive(Default)]
+struct PlayerListHandler {
+    tick: u32,
+    players: Vec<PlayerInfo>,
+}
+
+impl PlayerListHandler {
+    /// The player currently connected as `entity`
+    fn connected(&mut self, entity: EntityId) -> Option<&mut PlayerInfo> {
+        self.players
+            .iter_mut()
+            .find(|player| player.entity_id == entity && player.leave_tick.is_none())
+    }
+}
+
+impl MessageHandler for PlayerListHandler {
+    type Output = Vec<PlayerInfo>;
+
+    fn does_handle(message_type: MessageType) -> bool {
+        matches!(
+            message_type,
+            MessageType::CreateStringTable
+                | MessageType::UpdateStringTable
+                | MessageType::PacketEntities
+        )
+    }
+
+    fn handle_message(&mut self, message: &Message, _tick: u32, parser_state: &ParserState) {
+        if let Message::PacketEntities(entities) = message {
+            for entity in &entities.entities {
+                let player = match self.connected(entity.entity_index) {
+                    Some(player) => player,
+                    None => continue,
+                };
+                for prop in entity.props(parser_state) {
+                    match (prop.identifier, &prop.value) {
+                        (TEAM_PROP, SendPropValue::Integer(team)) => {
+                            player.team = Team::new(*team);
+                        }
+                        (CLASS_PROP, SendPropValue::Integer(class)) => {
+                            let class = Class::new(*class);
+                            if class != Class::Other && !player.classes.contains(&class) {
+                                player.classes.push(class);
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+
+    fn handle_string_entry(
+        &mut self,
+        table: &str,
+        index: usize,
+        entry: &StringTableEntry,
+        _parser_state: &ParserState,
+    ) {
+        if table != "userinfo" {
+            return;
+        }
+        let tick = self.tick;
+        match UserInfo::parse_from_string_table(
+            index as u16,
+            entry.text.as_deref(),
+            entry.extra_data.as_ref().map(|data| data.data.clone()),
+        ) {
+            Ok(Some(info)) => {
+                // user ids are unique per connection, updates to the same user only change the name
+                if let Some(existing) = self
+                    .players
+                    .iter_mut()
+                    .find(|player| player.user_id == info.player_info.user_id)
+                {
+                    existing.name = info.player_info.name;
+                    return;
+                }
+                // a new user in the same slot means the previous one left without the entry being cleared
+                if let Some(previous) = self.connected(info.entity_id) {
+                    previous.leave_tick = Some(tick);
+                }
+                let steam_id = SteamID::try_from(info.player_info.steam_id.as_str()).ok();
+                self.players.push(PlayerInfo {
+                    name: info.player_info.name,
+                    steam_id: steam_id.map(|steam_id| u64::from(steam_id).to_string()),
+                    steam_id3: steam_id.map(|steam_id| steam_id.steam3()),
+                    team: Team::Other,
+                    classes: Vec::new(),
+                    entity_id: info.entity_id,
+                    user_id: info.player_info.user_id,
+                    join_tick: tick,
+                    leave_tick: None,
+                });
+            }
+            Ok(None) => {
+                // the entry is cleared when the player in the slot disconnects
+                if let Some(previous) = self.connected(EntityId::from(index as u32 + 1)) {
+                    previous.leave_tick = Some(tick);
+                }
+            }
+            Err(_) => {}
+        }
+    }
+
+    fn handle_packet_meta(&mut self, tick: u32, _meta: &MessagePacketMeta, _state: &ParserState) {
+        self.tick = tick;
+    }
+
+    fn into_output(self, _state: &ParserState) -> Self::Output {
+        self.players
+    }
+}
@@ -1,28 +1,89 @@
-use demostf_client::{ApiClient, User};
-use std::collections::HashMap;
+use crate::analyser::TEAM_PROP;
+use crate::lookup::{search_demostf, LookupOptions};
+use crate::Error;
 use std::convert::TryFrom;
+use std::fmt::{self, Display, Formatter};
 use steamid_ng::SteamID;
 use tf_demo_parser::demo::data::UserInfo;
 use tf_demo_parser::demo::message::packetentities::EntityId;
 use tf_demo_parser::demo::message::Message;
 use tf_demo_parser::demo::packet::stringtable::StringTableEntry;
 use tf_demo_parser::demo::parser::analyser::UserId;
+use tf_demo_parser::demo::parser::gamestateanalyser::Team;
 use tf_demo_parser::demo::parser::MessageHandler;
+use tf_demo_parser::demo::sendprop::SendPropValue;
 use tf_demo_parser::{Demo, DemoParser, MessageType, ParserState};
-use tokio::runtime::Runtime;
-use tracing::error;
+use tracing::info;
 
-pub fn get_player(demo: &Demo, user: Option<String>) -> (EntityId, UserId) {
-    let parser = DemoParser::new_with_analyser(demo.get_stream(), PlayerSearchHandler::new(user));
+pub fn get_player(
+    demo: &Demo,
+    user: Option<String>,
+    lookup: &LookupOptions,
+) -> Result<(EntityId, UserId), Error> {
+    let parser =
+        DemoParser::new_with_analyser(demo.get_stream(), PlayerSearchHandler::new(user, lookup));
+    let resolver = parser.parse()?.1;
+    resolved_player(&resolver, lookup)
+}
+
+/// The entity and user id of the player once the entire demo has been parsed,
+/// the player doesn't have to be connected at the end of the demo
+fn resolved_player(
+    resolver: &PlayerResolver,
+    lookup: &LookupOptions,
+) -> Result<(EntityId, UserId), Error> {
+    if resolver.is_resolved() {
+        return resolver
+            .chosen()
+            .map(|candidate| (candidate.entity, candidate.user))
+            .ok_or(Error::PlayerNotFound);
+    }
+    let steam_id = resolver.resolve(lookup)?;
+    resolver
+        .candidates
+        .iter()
+        .find(|candidate| candidate.steam_id == Some(steam_id))
+        .map(|candidate| (candidate.entity, candidate.user))
+        .ok_or(Error::PlayerNotFound)
+}
 
-    parser
-        .parse()
-        .expect("failed to parse demo")
-        .1
-        .expect("no server info or player not found")
+/// Name matches are scored by how closely they match, only the best matches are considered
+const SCORE_EXACT: u8 = 4;
+const SCORE_PREFIX: u8 = 3;
+const SCORE_CONTAINS: u8 = 2;
+const SCORE_FUZZY: u8 = 1;
+
+/// Score how well the lowercased `filter` matches a player name, `0` if it doesn't match at all
+fn name_score(filter: &str, name: &str) -> u8 {
+    let name = name.to_lowercase();
+    if name == filter {
+        SCORE_EXACT
+    } else if name.starts_with(filter) {
+        SCORE_PREFIX
+    } else if name.contains(filter) {
+        SCORE_CONTAINS
+    } else {
+        // all characters of the filter in order, to allow skipping clan tags and decorations
+        let mut chars = name.chars();
+        if filter.chars().all(|c| chars.any(|n| n == c)) {
+            SCORE_FUZZY
+        } else {
+            0
+        }
+    }
+}
+
+#[test]
+fn test_name_score() {
+    assert_eq!(SCORE_EXACT, name_score("bob", "Bob"));
+    assert_eq!(SCORE_PREFIX, name_score("bob", "Bobby"));
+    assert_eq!(SCORE_CONTAINS, name_score("bob", "[TAG] Bob"));
+    assert_eq!(SCORE_FUZZY, name_score("bob", "B.o.B"));
+    assert_eq!(0, name_score("bob", "Alice"));
 }
 
 enum PlayerFilter {
+    /// Lowercased name
     Name(String),
     SteamId(SteamID),
 }
@@ -31,50 +92,527 @@ impl PlayerFilter {
     fn new(filter: String) -> Self {
         match SteamID::try_from(filter.as_str()) {
             Ok(steam_id) => PlayerFilter::SteamId(steam_id),
-            Err(_) => PlayerFilter::Name(filter),
+            Err(_) => PlayerFilter::Name(filter.to_lowercase()),
         }
     }
 
-    fn matches(&self, info: &UserInfo) -> bool {
+    fn score(&self, candidate: &Candidate) -> u8 {
         match self {
-            PlayerFilter::Name(name) => info.player_info.name.to_ascii_lowercase().contains(name),
-            PlayerFilter::SteamId(steam_id) => {
-                SteamID::try_from(info.player_info.steam_id.as_str()).ok() == Some(*steam_id)
+            PlayerFilter::Name(name) => name_score(name, &candidate.name),
+            PlayerFilter::SteamId(steam_id) if candidate.steam_id == Some(*steam_id) => SCORE_EXACT,
+            PlayerFilter::SteamId(_) => 0,
+        }
+    }
+}
+
+/// The parts of a `userinfo` entry needed to track a player
+#[derive(Clone, Debug)]
+pub struct UserEntry {
+    pub name: String,
+    pub steam_id: Option<SteamID>,
+    pub entity: EntityId,
+    pub user: UserId,
+}
+
+impl UserEntry {
+    /// Parse a `userinfo` entry, `Some(None)` if the entry was cleared because the player disconnected
+    pub fn parse(index: usize, entry: &StringTableEntry) -> Option<Option<Self>> {
+        match UserInfo::parse_from_string_table(
+            index as u16,
+            entry.text.as_deref(),
+            entry.extra_data.as_ref().map(|data| data.data.clone()),
+        ) {
+            Ok(Some(info)) => Some(Some(UserEntry {
+                steam_id: SteamID::try_from(info.player_info.steam_id.as_str()).ok(),
+                name: info.player_info.name,
+                entity: info.entity_id,
+                user: info.player_info.user_id,
+            })),
+            Ok(None) => Some(None),
+            Err(_) => None,
+        }
+    }
+}
+
+/// A player in the demo that might be the one being searched for
+#[derive(Clone, Debug)]
+pub struct Candidate {
+    pub name: String,
+    /// `None` for bots
+    pub steam_id: Option<SteamID>,
+    pub entity: EntityId,
+    pub user: UserId,
+    /// The last team the player was on
+    pub team: Team,
+    /// Every team the player was on during the demo
+    pub teams: Vec<Team>,
+    /// Whether the player is currently connected, the entity and user id are from the last connection
+    pub connected: bool,
+}
+
+impl Display for Candidate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let team = match self.team {
+            Team::Red => "red",
+            Team::Blue => "blue",
+            Team::Spectator => "spectator",
+            _ => "no team",
+        };
+        match self.steam_id {
+            Some(steam_id) => write!(f, "{} ({}, {})", self.name, steam_id.steam3(), team),
+            None => write!(f, "{} (bot, {})", self.name, team),
+        }
+    }
+}
+
+/// Why [`PlayerResolver::select`] couldn't select a single player, with the best matches
+enum SelectError {
+    Ambiguous(Vec<usize>),
+    PickOutOfRange(Vec<usize>),
+}
+
+/// Find the player to track from the `userinfo` string table while the demo is being parsed
+///
+/// Without a filter the player that recorded the demo is used.
+/// When a name matches multiple players, the best match is tracked during parsing
+/// and [`PlayerResolver::resolve`] decides between them once the entire demo is parsed.
+#[derive(Default)]
+pub struct PlayerResolver {
+    filter: Option<PlayerFilter>,
+    /// Steam ids the name filter is an alias for
+    aliases: Vec<SteamID>,
+    /// 1-based index into the best matches
+    pick: Option<usize>,
+    team: Option<Team>,
+    pov: Option<EntityId>,
+    /// Every player seen in the demo, one entry per steam id
+    candidates: Vec<Candidate>,
+    chosen: Option<usize>,
+    /// Whether a different player was chosen after the first choice
+    changed: bool,
+}
+
+impl PlayerResolver {
+    pub fn new(user: Option<String>, lookup: &LookupOptions) -> Self {
+        let filter = user.map(PlayerFilter::new);
+        let aliases = match &filter {
+            Some(PlayerFilter::Name(name)) => lookup.aliases.get(name).to_vec(),
+            _ => Vec::new(),
+        };
+        PlayerResolver {
+            filter,
+            aliases,
+            pick: lookup.pick,
+            team: lookup.team,
+            ..PlayerResolver::default()
+        }
+    }
+
+    /// The entity and user id of the player, if the player has been found yet and is currently connected
+    pub fn player(&self) -> Option<(EntityId, UserId)> {
+        let candidate = &self.candidates[self.chosen?];
+        if candidate.connected {
+            Some((candidate.entity, candidate.user))
+        } else {
+            None
+        }
+    }
+
+    /// The player chosen so far, even if currently disconnected
+    pub fn chosen(&self) -> Option<&Candidate> {
+        self.chosen.map(|index| &self.candidates[index])
+    }
+
+    /// Whether the player tracked during parsing is the final choice
+    ///
+    /// If not, the demo has to be parsed again with the steam id from [`PlayerResolver::resolve`].
+    pub fn is_resolved(&self) -> bool {
+        match self.filter {
+            None => self.chosen.is_some(),
+            Some(_) => {
+                !self.changed
+                    && matches!(self.select(), Ok(Some(index)) if Some(index) == self.chosen)
+            }
+        }
+    }
+
+    pub fn handle_message(&mut self, message: &Message, state: &ParserState) {
+        match message {
+            Message::ServerInfo(info) if self.filter.is_none() => {
+                self.pov = Some(EntityId::from(info.player_slot as u32 + 1));
+            }
+            Message::PacketEntities(entities) => {
+                let mut team_changed = false;
+                for entity in &entities.entities {
+                    for prop in entity.props(state) {
+                        if let (TEAM_PROP, SendPropValue::Integer(team)) =
+                            (prop.identifier, &prop.value)
+                        {
+                            team_changed |= self.handle_team(entity.entity_index, Team::new(*team));
+                        }
+                    }
+                }
+                if team_changed && self.team.is_some() {
+                    self.update();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Update the team of the player connected as `entity`, returns whether the team changed
+    fn handle_team(&mut self, entity: EntityId, team: Team) -> bool {
+        let mut changed = false;
+        for candidate in self
+            .candidates
+            .iter_mut()
+            .filter(|candidate| candidate.connected && candidate.entity == entity)
+        {
+            changed |= candidate.team != team;
+            candidate.team = team;
+            if !candidate.teams.contains(&team) {
+                candidate.teams.push(team);
             }
         }
+        changed
+    }
+
+    pub fn handle_user_info(&mut self, index: usize, entry: &StringTableEntry) {
+        if let Some(entry) = UserEntry::parse(index, entry) {
+            self.handle_user(index, entry);
+        }
+    }
+
+    /// Update the players from a `userinfo` entry, players are tracked by steam id across reconnects
+    ///
+    /// A `None` entry means the player in the slot disconnected.
+    pub fn handle_user(&mut self, index: usize, entry: Option<UserEntry>) {
+        // whoever was in this slot before is gone, if they are still connected they'll show up in a different slot
+        let slot = EntityId::from(index as u32 + 1);
+        for candidate in &mut self.candidates {
+            if candidate.entity == slot {
+                candidate.connected = false;
+            }
+        }
+
+        if let Some(entry) = entry {
+            let existing = self
+                .candidates
+                .iter()
+                .position(|candidate| match entry.steam_id {
+                    Some(_) => candidate.steam_id == entry.steam_id,
+                    None => candidate.user == entry.user,
+                });
+            match existing {
+                Some(existing) => {
+                    let candidate = &mut self.candidates[existing];
+                    if Some(existing) == self.chosen
+                        && (candidate.entity, candidate.user) != (entry.entity, entry.user)
+                    {
+                        info!(
+                            "{} reconnected as entity {}, user {}",
+                            entry.name,
+                            entry.entity,
+                            u8::from(entry.user)
+                        );
+                    }
+                    candidate.name = entry.name;
+                    candidate.entity = entry.entity;
+                    candidate.user = entry.user;
+                    candidate.connected = true;
+                }
+                None => self.candidates.push(Candidate {
+                    name: entry.name,
+                    steam_id: entry.steam_id,
+                    entity: entry.entity,
+                    user: entry.user,
+                    team: Team::Other,
+                    teams: Vec::new(),
+                    connected: true,
+                }),
+            }
+        }
+        self.update();
+    }
+
+    /// Choose the player to track with the players seen so far
+    fn update(&mut self) {
+        let chosen = match self.filter {
+            None => self
+                .candidates
+                .iter()
+                .position(|candidate| candidate.connected && Some(candidate.entity) == self.pov),
+            Some(_) => match self.select() {
+                Ok(chosen) => chosen,
+                // keep recording the first of the best matches, the ambiguity is reported at the end
+                Err(SelectError::Ambiguous(best)) => best.first().copied(),
+                // more players might still join
+                Err(SelectError::PickOutOfRange(_)) => None,
+            },
+        };
+        if let Some(index) = chosen {
+            if self.chosen != chosen {
+                self.changed |= self.chosen.is_some();
+                let candidate = &self.candidates[index];
+                info!(
+                    "Found {} as entity {}, user {}",
+                    candidate.name,
+                    candidate.entity,
+                    u8::from(candidate.user)
+                );
+            }
+            self.chosen = chosen;
+        }
+    }
+
+    /// Select the best match for the filter, fails with all best matches if they can't be told apart
+    /// or if the pick is out of range
+    fn select(&self) -> Result<Option<usize>, SelectError> {
+        let filter = match &self.filter {
+            Some(filter) => filter,
+            None => return Ok(None),
+        };
+        // a steam id can only match a single player
+        let (team, pick) = match filter {
+            PlayerFilter::Name(_) => (self.team, self.pick),
+            PlayerFilter::SteamId(_) => (None, None),
+        };
+        let scores: Vec<(usize, u8)> = self
+            .candidates
+            .iter()
+            .enumerate()
+            .filter(|(_, candidate)| match team {
+                Some(team) => candidate.teams.contains(&team),
+                None => true,
+            })
+            .map(|(index, candidate)| (index, filter.score(candidate)))
+            .filter(|(_, score)| *score > 0)
+            .collect();
+        let best_score = scores.iter().map(|(_, score)| *score).max().unwrap_or(0);
+        // an alias for the name wins over a partial match of someone else's name
+        if best_score < SCORE_EXACT {
+            let alias = self.aliases.iter().find_map(|steam_id| {
+                self.candidates
+                    .iter()
+                    .position(|candidate| candidate.steam_id == Some(*steam_id))
+            });
+            if alias.is_some() {
+                return Ok(alias);
+            }
+        }
+        if best_score == 0 {
+            return Ok(None);
+        }
+        let mut best: Vec<usize> = scores
+            .into_iter()
+            .filter(|(_, score)| *score == best_score)
+            .map(|(index, _)| index)
+            .collect();
+        best.sort_by_key(|index| self.candidates[*index].entity);
+
+        match (pick, best.len()) {
+            (Some(pick), _) => match pick.checked_sub(1).and_then(|pick| best.get(pick)) {
+                Some(index) => Ok(Some(*index)),
+                None => Err(SelectError::PickOutOfRange(best)),
+            },
+            (None, 1) => Ok(Some(best[0])),
+            (None, _) => Err(SelectError::Ambiguous(best)),
+        }
+    }
+
+    /// Decide on the player once the entire demo has been parsed and return the steam id to track
+    ///
+    /// Fails if multiple players match equally well, falls back to [`PlayerResolver::search`] if nobody matches.
+    pub fn resolve(&self, lookup: &LookupOptions) -> Result<SteamID, Error> {
+        match self.select() {
+            Ok(Some(index)) => self.candidates[index].steam_id.ok_or(Error::PlayerNotFound),
+            Ok(None) => self
+                .search(lookup)?
+                .map(|(steam_id, _, _)| steam_id)
+                .ok_or(Error::PlayerNotFound),
+            Err(SelectError::Ambiguous(best)) => Err(Error::AmbiguousPlayer {
+                candidates: self.describe(&best),
+            }),
+            Err(SelectError::PickOutOfRange(best)) => Err(Error::PickOutOfRange {
+                pick: self.pick.unwrap_or_default(),
+                candidates: self.describe(&best),
+            }),
+        }
+    }
+
+    /// The candidates numbered the way `--pick` selects them
+    fn describe(&self, best: &[usize]) -> Vec<String> {
+        best.iter()
+            .enumerate()
+            .map(|(pick, index)| format!("{}: {}", pick + 1, self.candidates[*index]))
+            .collect()
+    }
+
+    /// Find the steam ids for the name filter on demos.tf and check if any of them are in the demo
+    ///
+    /// Only used as a fallback when neither a player's name nor any of the aliases for the name match a player in the demo.
+    pub fn search(
+        &self,
+        options: &LookupOptions,
+    ) -> Result<Option<(SteamID, EntityId, UserId)>, Error> {
+        let name = match &self.filter {
+            Some(PlayerFilter::Name(name)) => name,
+            _ => return Ok(None),
+        };
+        if options.offline {
+            return Ok(None);
+        }
+        Ok(self.find_any(search_demostf(name, options)?))
+    }
+
+    fn find_any(
+        &self,
+        steam_ids: impl IntoIterator<Item = SteamID>,
+    ) -> Option<(SteamID, EntityId, UserId)> {
+        steam_ids.into_iter().find_map(|steam_id| {
+            let candidate = self
+                .candidates
+                .iter()
+                .find(|candidate| candidate.steam_id == Some(steam_id))?;
+            info!(
+                "Found {} as entity {}, user {}",
+                candidate.name,
+                candidate.entity,
+                u8::from(candidate.user)
+            );
+            Some((steam_id, candidate.entity, candidate.user))
+        })
     }
 }
+
+#[cfg(test)]
+fn entry(name: &str, account: u32, slot: u32, user: u16) -> Option<UserEntry> {
+    Some(UserEntry {
+        name: name.to_string(),
+        steam_id: SteamID::try_from(format!("[U:1:{}]", account).as_str()).ok(),
+        entity: EntityId::from(slot + 1),
+        user: UserId::from(user),
+    })
+}
+
+#[test]
+fn test_follow_reconnect() {
+    let mut resolver = PlayerResolver::new(Some("bob".to_string()), &LookupOptions::default());
+    resolver.handle_user(1, entry("alice", 1, 1, 2));
+    resolver.handle_user(2, entry("bob", 2, 2, 3));
+    assert_eq!(
+        Some((EntityId::from(3u32), UserId::from(3u16))),
+        resolver.player()
+    );
+
+    // bob disconnects and someone else takes the slot
+    resolver.handle_user(2, None);
+    assert_eq!(None, resolver.player());
+    resolver.handle_user(2, entry("carol", 3, 2, 4));
+    assert_eq!(None, resolver.player());
+
+    // bob reconnects in a different slot with a new user id
+    resolver.handle_user(4, entry("bob", 2, 4, 5));
+    assert_eq!(
+        Some((EntityId::from(5u32), UserId::from(5u16))),
+        resolver.player()
+    );
+    assert!(resolver.is_resolved());
+}
+
+#[test]
+fn test_player_left_before_end() {
+    let lookup = LookupOptions {
+        offline: true,
+        ..LookupOptions::default()
+    };
+    let mut resolver = PlayerResolver::new(Some("bob".to_string()), &lookup);
+    resolver.handle_user(1, entry("alice", 1, 1, 2));
+    resolver.handle_user(2, entry("bob", 2, 2, 3));
+    resolver.handle_user(2, None);
+
+    assert_eq!(None, resolver.player());
+    assert_eq!(
+        (EntityId::from(3u32), UserId::from(3u16)),
+        resolved_player(&resolver, &lookup).unwrap()
+    );
+}
+
+#[test]
+fn test_pick_and_team() {
+    let lookup = LookupOptions {
+        pick: Some(3),
+        ..LookupOptions::default()
+    };
+    let mut resolver = PlayerResolver::new(Some("bob".to_string()), &lookup);
+    resolver.handle_user(1, entry("bob", 1, 1, 2));
+    resolver.handle_user(2, entry("bob", 2, 2, 3));
+    assert!(matches!(
+        resolver.resolve(&lookup),
+        Err(Error::PickOutOfRange { pick: 3, ref candidates }) if candidates.len() == 2
+    ));
+
+    // players are matched on any team they were on, not just the last one
+    let lookup = LookupOptions {
+        team: Some(Team::Red),
+        ..LookupOptions::default()
+    };
+    let mut resolver = PlayerResolver::new(Some("bob".to_string()), &lookup);
+    resolver.handle_user(1, entry("bob", 1, 1, 2));
+    resolver.handle_user(2, entry("bob", 2, 2, 3));
+    resolver.handle_team(EntityId::from(2u32), Team::Blue);
+    resolver.handle_team(EntityId::from(3u32), Team::Red);
+    resolver.handle_team(EntityId::from(3u32), Team::Blue);
+    resolver.update();
+    assert_eq!(
+        Some((EntityId::from(3u32), UserId::from(3u16))),
+        resolver.player()
+    );
+}
+
+#[test]
+fn test_alias_before_partial_match() {
+    let lookup = LookupOptions {
+        aliases: crate::lookup::Aliases::from_toml(r#"bob = "[U:1:7]""#).unwrap(),
+        ..LookupOptions::default()
+    };
+    let mut resolver = PlayerResolver::new(Some("bob".to_string()), &lookup);
+    resolver.handle_user(1, entry("bobby", 1, 1, 2));
+    resolver.handle_user(2, entry("robert", 7, 2, 3));
+    assert_eq!(
+        Some((EntityId::from(3u32), UserId::from(3u16))),
+        resolver.player()
+    );
+
+    // an exact name match still wins over the alias
+    resolver.handle_user(3, entry("Bob", 8, 3, 4));
+    assert_eq!(
+        Some((EntityId::from(4u32), UserId::from(4u16))),
+        resolver.player()
+    );
+}
+
 struct PlayerSearchHandler {
-    filter: Option<PlayerFilter>,
-    entity: Option<EntityId>,
-    user: Option<UserId>,
-    all_users: HashMap<SteamID, (String, EntityId, UserId)>,
+    resolver: PlayerResolver,
 }
 
 impl PlayerSearchHandler {
-    pub fn new(user: Option<String>) -> Self {
+    pub fn new(user: Option<String>, lookup: &LookupOptions) -> Self {
         PlayerSearchHandler {
-            filter: user.map(PlayerFilter::new),
-            entity: None,
-            user: None,
-            all_users: HashMap::default(),
+            resolver: PlayerResolver::new(user, lookup),
         }
     }
 }
 
 impl MessageHandler for PlayerSearchHandler {
-    type Output = Option<(EntityId, UserId)>;
+    type Output = PlayerResolver;
 
     fn does_handle(_message_type: MessageType) -> bool {
         true
     }
 
-    fn handle_message(&mut self, message: &Message, _tick: u32, _parser_state: &ParserState) {
-        if self.filter.is_none() {
-            if let Message::ServerInfo(info) = message {
-                self.entity = Some(EntityId::from(info.player_slot as u32 + 1));
-            }
-        }
+    fn handle_message(&mut self, message: &Message, _tick: u32, parser_state: &ParserState) {
+        self.resolver.handle_message(message, parser_state);
     }
 
     fn handle_string_entry(
@@ -85,70 +623,11 @@ impl MessageHandler for PlayerSearchHandler {
         _parser_state: &ParserState,
     ) {
         if table == "userinfo" {
-            if let Ok(Some(info)) = UserInfo::parse_from_string_table(
-                index as u16,
-                entry.text.as_deref(),
-                entry.extra_data.as_ref().map(|data| data.data.clone()),
-            ) {
-                if let Ok(steam_id) = SteamID::try_from(info.player_info.steam_id.as_str()) {
-                    self.all_users.insert(
-                        steam_id,
-                        (
-                            info.player_info.name.clone(),
-                            info.entity_id,
-                            info.player_info.user_id,
-                        ),
-                    );
-                }
-                if let Some(filter) = self.filter.as_ref() {
-                    if filter.matches(&info) && self.entity.is_none() {
-                        println!(
-                            "Found {} as entity {}, user {}",
-                            info.player_info.name,
-                            info.entity_id,
-                            u8::from(info.player_info.user_id)
-                        );
-                        self.entity = Some(info.entity_id);
-                        self.user = Some(info.player_info.user_id);
-                    }
-                } else {
-                    if Some(info.entity_id) == self.entity && self.filter.is_none() {
-                        self.user = Some(info.player_info.user_id);
-                    }
-                }
-            }
+            self.resolver.handle_user_info(index, entry);
         }
     }
 
-    fn into_output(mut self, _state: &ParserState) -> Self::Output {
-        if let (Some(entity), Some(user)) = (self.entity, self.user) {
-            return Some((entity, user));
-        }
-        if let Some(PlayerFilter::Name(name)) = self.filter {
-            let possible_users = get_steam_ids(&name);
-            for possible_user in possible_users {
-                if let Some((name, entity, user)) = self.all_users.remove(&possible_user.steam_id) {
-                    println!(
-                        "Found {} as entity {}, user {}",
-                        name,
-                        entity,
-                        u8::from(user)
-                    );
-                    return Some((entity, user));
-                }
-            }
-        }
-        None
+    fn into_output(self, _state: &ParserState) -> Self::Output {
+        self.resolver
     }
 }
-
-fn get_steam_ids(name: &str) -> Vec<User> {
-    let rt = Runtime::new().unwrap();
-    rt.block_on(async {
-        let client = ApiClient::new();
-        client.search_users(name).await.unwrap_or_else(|e| {
-            error!(error = display(e), "Failed to search users");
-            Vec::new()
-        })
-    })
-}
@@ -0,0 +1,187 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// Output frame rate, stored as a fraction so NTSC rates like 30000/1001 are exact
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FrameRate {
+    numerator: u32,
+    denominator: u32,
+}
+
+impl FrameRate {
+    pub const fn new(numerator: u32, denominator: u32) -> Self {
+        FrameRate {
+            numerator,
+            denominator,
+        }
+    }
+
+    pub fn fps(&self) -> f64 {
+        self.numerator as f64 / self.denominator as f64
+    }
+}
+
+impl Default for FrameRate {
+    fn default() -> Self {
+        FrameRate::new(120, 1)
+    }
+}
+
+impl fmt::Display for FrameRate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.denominator == 1 {
+            write!(f, "{}", self.numerator)
+        } else {
+            write!(f, "{:.3}", self.fps())
+        }
+    }
+}
+
+impl FromStr for FrameRate {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid frame rate \"{}\"", s);
+        let rate = if let Some((numerator, denominator)) = s.split_once('/') {
+            FrameRate::new(
+                numerator.trim().parse().map_err(|_| invalid())?,
+                denominator.trim().parse().map_err(|_| invalid())?,
+            )
+        } else {
+            match s.trim() {
+                // the common NTSC rates are written rounded, but mean the 1000/1001 variant
+                "23.976" | "23.98" => FrameRate::new(24000, 1001),
+                "29.97" => FrameRate::new(30000, 1001),
+                "47.952" | "47.95" => FrameRate::new(48000, 1001),
+                "59.94" => FrameRate::new(60000, 1001),
+                "119.88" => FrameRate::new(120000, 1001),
+                rate => {
+                    let (whole, fraction) = rate.split_once('.').unwrap_or((rate, ""));
+                    if fraction.len() > 3 || !fraction.chars().all(|c| c.is_ascii_digit()) {
+                        return Err(invalid());
+                    }
+                    let denominator = 10u32.pow(fraction.len() as u32);
+                    let whole: u32 = whole.parse().map_err(|_| invalid())?;
+                    let fraction: u32 = if fraction.is_empty() {
+                        0
+                    } else {
+                        fraction.parse().map_err(|_| invalid())?
+                    };
+                    let numerator = whole
+                        .checked_mul(denominator)
+                        .and_then(|whole| whole.checked_add(fraction))
+                        .ok_or_else(invalid)?;
+                    FrameRate::new(numerator, denominator)
+                }
+            }
+        };
+        if rate.numerator == 0 || rate.denominator == 0 {
+            return Err(invalid());
+        }
+        Ok(rate)
+    }
+}
+
+#[test]
+fn test_parse_frame_rate() {
+    assert_eq!(Ok(FrameRate::new(120, 1)), "120".parse());
+    assert_eq!(Ok(FrameRate::new(60, 1)), "60".parse());
+    assert_eq!(Ok(FrameRate::new(30000, 1001)), "29.97".parse());
+    assert_eq!(Ok(FrameRate::new(60000, 1001)), "59.94".parse());
+    assert_eq!(Ok(FrameRate::new(60000, 1001)), "60000/1001".parse());
+    assert_eq!(Ok(FrameRate::new(255, 10)), "25.5".parse());
+    assert!("0".parse::<FrameRate>().is_err());
+    assert!("60/0".parse::<FrameRate>().is_err());
+    assert!("fast".parse::<FrameRate>().is_err());
+    assert!("-60".parse::<FrameRate>().is_err());
+    assert_eq!(Ok(FrameRate::new(u32::MAX, 1000)), "4294967.295".parse());
+    assert!("4294967.296".parse::<FrameRate>().is_err());
+    assert!("4294968.000".parse::<FrameRate>().is_err());
+}
+
+/// Mapping between demo ticks and output frames
+#[derive(Clone, Copy, Debug)]
+pub struct Timing {
+    start: u32,
+    time_per_tick: f64,
+    frame_rate: FrameRate,
+}
+
+impl Timing {
+    pub fn new(start: u32, time_per_tick: f32, frame_rate: FrameRate) -> Self {
+        Timing {
+            start,
+            time_per_tick: time_per_tick as f64,
+            frame_rate,
+        }
+    }
+
+    pub fn frame_rate(&self) -> FrameRate {
+        self.frame_rate
+    }
+
+    /// The first frame that is shown at or after the tick
+    pub fn frame_for_tick(&self, tick: u32) -> i32 {
+        let seconds = (tick as f64 - self.start as f64) * self.time_per_tick;
+        let frame = seconds * self.fps();
+        // prevent rounding errors from pushing the frame for a tick that falls exactly on a frame back by one
+        (frame + 0.0001).floor() as i32
+    }
+
+    /// The (fractional) tick shown at the frame
+    pub fn tick_for_frame(&self, frame: i32) -> f32 {
+        let seconds = frame as f64 / self.fps();
+        (self.start as f64 + seconds / self.time_per_tick) as f32
+    }
+
+    /// Demo time in seconds at the (fractional) tick
+    pub fn demo_time(&self, tick: f32) -> f64 {
+        tick as f64 * self.time_per_tick
+    }
+
+    fn fps(&self) -> f64 {
+        self.frame_rate.fps()
+    }
+}
+
+#[test]
+fn test_frame_counts() {
+    let time_per_tick = 0.015;
+
+    let timing = Timing::new(1000, time_per_tick, FrameRate::new(120, 1));
+    assert_eq!(0, timing.frame_for_tick(1000));
+    assert_eq!(1800, timing.frame_for_tick(2000));
+
+    let timing = Timing::new(1000, time_per_tick, FrameRate::new(60, 1));
+    assert_eq!(900, timing.frame_for_tick(2000));
+
+    let timing = Timing::new(1000, time_per_tick, FrameRate::new(240, 1));
+    assert_eq!(3600, timing.frame_for_tick(2000));
+
+    let timing = Timing::new(1000, time_per_tick, FrameRate::new(30000, 1001));
+    assert_eq!(449, timing.frame_for_tick(2000));
+
+    let timing = Timing::new(1000, time_per_tick, FrameRate::new(60000, 1001));
+    assert_eq!(899, timing.frame_for_tick(2000));
+}
+
+#[test]
+fn test_tick_frame_roundtrip() {
+    for rate in ["60", "120", "240", "29.97", "59.94"] {
+        let timing = Timing::new(500, 0.015, rate.parse().unwrap());
+        for frame in 0..2000 {
+            let tick = timing.tick_for_frame(frame);
+            assert!(tick >= 500.0);
+
+            // every frame maps back onto itself
+            let seconds = (tick as f64 - 500.0) * 0.015;
+            assert_eq!(frame, (seconds * timing.fps()).round() as i32);
+        }
+        for tick in 500..1500 {
+            let frame = timing.frame_for_tick(tick);
+            // the frame for a tick never shows a later tick
+            assert!(timing.tick_for_frame(frame) <= tick as f32 + 0.001);
+            assert!(timing.tick_for_frame(frame + 1) > tick as f32);
+        }
+    }
+}
@@ -23,15 +23,57 @@ fn test_unwrap() {
     assert_eq!((120.0, 99.0), unwrap::<-100, 100>(-80.0, 99.0));
 }
 
-fn wrap<const MIN: i32, const MAX: i32>(num: f32) -> f32 {
+/// Map every point such that the difference to the previous point is the wrapping difference between them
+///
+/// The first point is left as is, which keeps the curve through the points continuous even if it crosses the edge multiple times
+fn unwrap_all<const MIN: i32, const MAX: i32, const N: usize>(mut points: [f32; N]) -> [f32; N] {
+    let range = (MAX - MIN) as f32;
+    for i in 1..N {
+        // the previous point can already be outside of the range, so only its wrapped difference is used
+        let delta = (points[i] - points[i - 1] - MIN as f32).rem_euclid(range) + MIN as f32;
+        points[i] = points[i - 1] + delta;
+    }
+    points
+}
+
+#[test]
+fn test_unwrap_all() {
+    assert_eq!(
+        [170.0, 190.0, 210.0, 230.0],
+        unwrap_all::<-180, 180, 4>([170.0, -170.0, -150.0, -130.0])
+    );
+    assert_eq!(
+        [-170.0, -190.0, -210.0],
+        unwrap_all::<-180, 180, 3>([-170.0, 170.0, 150.0])
+    );
+    assert_eq!(
+        [170.0, 190.0, 170.0, 150.0],
+        unwrap_all::<-180, 180, 4>([170.0, -170.0, 170.0, 150.0])
+    );
+    assert_eq!(
+        [10.0, 20.0, -10.0],
+        unwrap_all::<-180, 180, 3>([10.0, 20.0, -10.0])
+    );
+    // spinning past a full turn keeps unwrapping relative to the already unwrapped points
+    assert_eq!(
+        [170.0, 290.0, 410.0, 550.0],
+        unwrap_all::<-180, 180, 4>([170.0, -70.0, 50.0, -170.0])
+    );
+    assert_eq!(
+        [-170.0, -290.0, -410.0, -550.0],
+        unwrap_all::<-180, 180, 4>([-170.0, 70.0, -50.0, 170.0])
+    );
+}
+
+fn wrap<const MIN: i32, const MAX: i32>(mut num: f32) -> f32 {
     let offset = (MAX - MIN) as f32;
-    if num > MAX as f32 {
-        num - offset
-    } else if num < MIN as f32 {
-        num + offset
-    } else {
-        num
+    while num > MAX as f32 {
+        num -= offset;
+    }
+    while num < MIN as f32 {
+        num += offset;
     }
+    num
 }
 
 impl<const MIN: i32, const MAX: i32> Interpolate<f32> for Wrapping<MIN, MAX> {
@@ -56,25 +98,41 @@ impl<const MIN: i32, const MAX: i32> Interpolate<f32> for Wrapping<MIN, MAX> {
     }
 
     fn cubic_hermite(
-        _t: f32,
-        _x: (f32, Self),
-        _a: (f32, Self),
-        _b: (f32, Self),
-        _y: (f32, Self),
+        t: f32,
+        x: (f32, Self),
+        a: (f32, Self),
+        b: (f32, Self),
+        y: (f32, Self),
     ) -> Self {
-        todo!();
+        let [x_value, a_value, b_value, y_value] =
+            unwrap_all::<MIN, MAX, 4>([x.1 .0, a.1 .0, b.1 .0, y.1 .0]);
+        let c = f32::cubic_hermite(
+            t,
+            (x.0, x_value),
+            (a.0, a_value),
+            (b.0, b_value),
+            (y.0, y_value),
+        );
+        Wrapping(wrap::<MIN, MAX>(c))
     }
 
-    fn quadratic_bezier(_t: f32, _a: Self, _u: Self, _b: Self) -> Self {
-        todo!();
+    fn quadratic_bezier(t: f32, a: Self, u: Self, b: Self) -> Self {
+        let [a, u, b] = unwrap_all::<MIN, MAX, 3>([a.0, u.0, b.0]);
+        let c = f32::quadratic_bezier(t, a, u, b);
+        Wrapping(wrap::<MIN, MAX>(c))
     }
 
-    fn cubic_bezier(_t: f32, _a: Self, _u: Self, _v: Self, _b: Self) -> Self {
-        todo!();
+    fn cubic_bezier(t: f32, a: Self, u: Self, v: Self, b: Self) -> Self {
+        let [a, u, v, b] = unwrap_all::<MIN, MAX, 4>([a.0, u.0, v.0, b.0]);
+        let c = f32::cubic_bezier(t, a, u, v, b);
+        Wrapping(wrap::<MIN, MAX>(c))
     }
 
-    fn cubic_bezier_mirrored(_t: f32, _a: Self, _u: Self, _v: Self, _b: Self) -> Self {
-        todo!()
+    fn cubic_bezier_mirrored(t: f32, a: Self, u: Self, v: Self, b: Self) -> Self {
+        // `v` is mirrored around `b`, so it has to be unwrapped relative to `b` instead of `u`
+        let [a, u, b, v] = unwrap_all::<MIN, MAX, 4>([a.0, u.0, b.0, v.0]);
+        let c = f32::cubic_bezier_mirrored(t, a, u, v, b);
+        Wrapping(wrap::<MIN, MAX>(c))
     }
 }
 
@@ -90,3 +148,149 @@ fn test_wrapping_interp() {
     assert_eq!(180.0, spline.sample(5.0).unwrap().0);
     assert_eq!(-172.0, spline.sample(7.0).unwrap().0);
 }
+
+#[cfg(test)]
+fn assert_angle_eq(expected: f32, actual: Wrapping<-180, 180>) {
+    assert!(
+        (expected - actual.0).abs() < 0.001,
+        "expected {}, got {}",
+        expected,
+        actual.0
+    );
+}
+
+#[test]
+fn test_wrapping_interp_step() {
+    use splines::{Interpolation, Key, Spline};
+
+    let spline = Spline::from_vec(vec![
+        Key::new(0.0, Wrapping::<-180, 180>(170.0), Interpolation::Step(0.5)),
+        Key::new(
+            10.0,
+            Wrapping::<-180, 180>(-170.0),
+            Interpolation::Step(0.5),
+        ),
+    ]);
+    assert_eq!(170.0, spline.sample(2.0).unwrap().0);
+    assert_eq!(-170.0, spline.sample(7.0).unwrap().0);
+}
+
+#[test]
+fn test_wrapping_interp_cosine() {
+    use splines::{Interpolation, Key, Spline};
+
+    let spline = Spline::from_vec(vec![
+        Key::new(0.0, Wrapping::<-180, 180>(160.0), Interpolation::Cosine),
+        Key::new(10.0, Wrapping::<-180, 180>(-160.0), Interpolation::Cosine),
+    ]);
+    assert_angle_eq(180.0, spline.sample(5.0).unwrap());
+    assert_angle_eq(-168.244, spline.sample(7.0).unwrap());
+}
+
+#[test]
+fn test_wrapping_interp_catmull_rom() {
+    use splines::{Interpolation, Key, Spline};
+
+    let spline = Spline::from_vec(vec![
+        Key::new(0.0, Wrapping::<-180, 180>(150.0), Interpolation::CatmullRom),
+        Key::new(
+            10.0,
+            Wrapping::<-180, 180>(170.0),
+            Interpolation::CatmullRom,
+        ),
+        Key::new(
+            20.0,
+            Wrapping::<-180, 180>(-170.0),
+            Interpolation::CatmullRom,
+        ),
+        Key::new(
+            30.0,
+            Wrapping::<-180, 180>(-150.0),
+            Interpolation::CatmullRom,
+        ),
+    ]);
+    assert_angle_eq(174.0, spline.sample(12.0).unwrap());
+    assert_angle_eq(180.0, spline.sample(15.0).unwrap());
+    assert_angle_eq(-174.0, spline.sample(18.0).unwrap());
+
+    // the neighbouring keys can be on the other side of the edge
+    let spline = Spline::from_vec(vec![
+        Key::new(
+            0.0,
+            Wrapping::<-180, 180>(-170.0),
+            Interpolation::CatmullRom,
+        ),
+        Key::new(
+            10.0,
+            Wrapping::<-180, 180>(170.0),
+            Interpolation::CatmullRom,
+        ),
+        Key::new(
+            20.0,
+            Wrapping::<-180, 180>(150.0),
+            Interpolation::CatmullRom,
+        ),
+        Key::new(
+            30.0,
+            Wrapping::<-180, 180>(130.0),
+            Interpolation::CatmullRom,
+        ),
+    ]);
+    assert_angle_eq(166.0, spline.sample(12.0).unwrap());
+    assert_angle_eq(160.0, spline.sample(15.0).unwrap());
+}
+
+#[test]
+fn test_wrapping_interp_quadratic_bezier() {
+    use splines::{Interpolation, Key, Spline};
+
+    let spline = Spline::from_vec(vec![
+        Key::new(
+            0.0,
+            Wrapping::<-180, 180>(170.0),
+            Interpolation::Bezier(Wrapping(175.0)),
+        ),
+        Key::new(10.0, Wrapping::<-180, 180>(-170.0), Interpolation::Linear),
+    ]);
+    assert_angle_eq(177.5, spline.sample(5.0).unwrap());
+    assert_angle_eq(-178.1, spline.sample(7.0).unwrap());
+}
+
+#[test]
+fn test_wrapping_interp_cubic_bezier() {
+    use splines::{Interpolation, Key, Spline};
+
+    let spline = Spline::from_vec(vec![
+        Key::new(
+            0.0,
+            Wrapping::<-180, 180>(170.0),
+            Interpolation::Bezier(Wrapping(175.0)),
+        ),
+        Key::new(
+            10.0,
+            Wrapping::<-180, 180>(-170.0),
+            Interpolation::StrokeBezier(Wrapping(-175.0), Wrapping(-165.0)),
+        ),
+    ]);
+    assert_angle_eq(180.0, spline.sample(5.0).unwrap());
+    assert_angle_eq(-175.58, spline.sample(7.0).unwrap());
+}
+
+#[test]
+fn test_wrapping_interp_cubic_bezier_mirrored() {
+    use splines::{Interpolation, Key, Spline};
+
+    let spline = Spline::from_vec(vec![
+        Key::new(
+            0.0,
+            Wrapping::<-180, 180>(170.0),
+            Interpolation::Bezier(Wrapping(175.0)),
+        ),
+        Key::new(
+            10.0,
+            Wrapping::<-180, 180>(-170.0),
+            Interpolation::Bezier(Wrapping(-175.0)),
+        ),
+    ]);
+    assert_angle_eq(-176.25, spline.sample(5.0).unwrap());
+}